@@ -16,3 +16,8 @@ cd crates/rewarder
 cargo near build reproducible-wasm
 cd ../..
 cp target/near/rewarder/rewarder.wasm ./res/
+
+cd crates/badge
+cargo near build reproducible-wasm
+cd ../..
+cp target/near/badge/badge.wasm ./res/
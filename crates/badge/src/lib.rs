@@ -0,0 +1,133 @@
+use near_contract_standards::non_fungible_token::{
+    NonFungibleToken, Token, TokenId,
+    core::NonFungibleTokenCore,
+    enumeration::NonFungibleTokenEnumeration,
+    metadata::{
+        NFT_METADATA_SPEC, NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata,
+    },
+};
+use near_sdk::{
+    AccountId, BorshStorageKey, PanicOnDefault, PromiseOrValue, borsh::BorshSerialize, env,
+    json_types::U128, near, require,
+};
+
+/// Soulbound achievement badges. Only the minter can mint and badges can never be transferred.
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Contract {
+    minter: AccountId,
+    tokens: NonFungibleToken,
+    metadata: NFTContractMetadata,
+}
+
+#[derive(BorshStorageKey, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub enum StorageKey {
+    NonFungibleToken,
+    TokenMetadata,
+    Enumeration,
+}
+
+#[near]
+impl Contract {
+    #[init]
+    pub fn new(minter: AccountId, name: String, symbol: String) -> Self {
+        Self {
+            tokens: NonFungibleToken::new(
+                StorageKey::NonFungibleToken,
+                minter.clone(),
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                None::<StorageKey>,
+            ),
+            minter,
+            metadata: NFTContractMetadata {
+                spec: NFT_METADATA_SPEC.to_string(),
+                name,
+                symbol,
+                icon: None,
+                base_uri: None,
+                reference: None,
+                reference_hash: None,
+            },
+        }
+    }
+
+    #[payable]
+    pub fn nft_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        token_metadata: TokenMetadata,
+    ) -> Token {
+        require!(
+            env::predecessor_account_id() == self.minter,
+            "Only minter can mint badges"
+        );
+
+        self.tokens
+            .internal_mint(token_id, token_owner_id, Some(token_metadata))
+    }
+}
+
+#[near]
+impl NonFungibleTokenCore for Contract {
+    #[payable]
+    fn nft_transfer(
+        &mut self,
+        _receiver_id: AccountId,
+        _token_id: TokenId,
+        _approval_id: Option<u64>,
+        _memo: Option<String>,
+    ) {
+        env::panic_str("Badges are soulbound");
+    }
+
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        _receiver_id: AccountId,
+        _token_id: TokenId,
+        _approval_id: Option<u64>,
+        _memo: Option<String>,
+        _msg: String,
+    ) -> PromiseOrValue<bool> {
+        env::panic_str("Badges are soulbound");
+    }
+
+    fn nft_token(&self, token_id: TokenId) -> Option<Token> {
+        self.tokens.nft_token(token_id)
+    }
+}
+
+#[near]
+impl NonFungibleTokenEnumeration for Contract {
+    fn nft_total_supply(&self) -> U128 {
+        self.tokens.nft_total_supply()
+    }
+
+    fn nft_tokens(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Token> {
+        self.tokens.nft_tokens(from_index, limit)
+    }
+
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128 {
+        self.tokens.nft_supply_for_owner(account_id)
+    }
+
+    fn nft_tokens_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        self.tokens
+            .nft_tokens_for_owner(account_id, from_index, limit)
+    }
+}
+
+#[near]
+impl NonFungibleTokenMetadataProvider for Contract {
+    fn nft_metadata(&self) -> NFTContractMetadata {
+        self.metadata.clone()
+    }
+}
@@ -1,5 +1,5 @@
 use near_contract_standards::non_fungible_token::{Token, TokenId};
-use near_sdk::{json_types::U128, AccountId, NearToken};
+use near_sdk::{AccountId, NearToken, json_types::U128};
 use near_workspaces::{Account, Contract};
 use serde_json::json;
 
@@ -121,12 +121,19 @@ pub async fn send_rewards(
     contract: &AccountId,
     account_id: &AccountId,
     amount: U128,
+    reward_id: &str,
 ) -> anyhow::Result<Vec<ContractEvent>> {
     let (_, events) = log_tx_result(
         "send_rewards",
         operator
             .call(contract, "send_rewards")
-            .args_json((account_id, amount))
+            .args_json((
+                account_id,
+                amount,
+                reward_id,
+                None::<String>,
+                None::<String>,
+            ))
             .max_gas()
             .transact()
             .await?,
@@ -206,6 +213,49 @@ pub async fn whitelist(
     Ok(events)
 }
 
+pub async fn set_badge_config(
+    owner: &Account,
+    rewarder: &AccountId,
+    badge: &AccountId,
+    milestones: Vec<U128>,
+) -> anyhow::Result<Vec<ContractEvent>> {
+    let (_, events) = log_tx_result(
+        "set_badge_config",
+        owner
+            .call(rewarder, "set_badge_config")
+            .args_json(json!({
+                "config": {
+                    "contract": badge,
+                    "milestones": milestones,
+                    "mint_deposit": NearToken::from_millinear(100).as_yoctonear().to_string(),
+                }
+            }))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+
+    Ok(events)
+}
+
+pub async fn claim_badges(
+    sender: &Account,
+    rewarder: &AccountId,
+    token_id: &TokenId,
+) -> anyhow::Result<Vec<ContractEvent>> {
+    let (_, events) = log_tx_result(
+        "claim_badges",
+        sender
+            .call(rewarder, "claim_badges")
+            .args_json((token_id,))
+            .max_gas()
+            .transact()
+            .await?,
+    )?;
+
+    Ok(events)
+}
+
 pub async fn stake_seed(
     staker: &Account,
     token: &AccountId,
@@ -1,8 +1,8 @@
 use near_contract_standards::non_fungible_token::TokenId;
 use near_sdk::{
+    AccountId,
     json_types::U128,
     serde::{Deserialize, Serialize},
-    AccountId,
 };
 use owo_colors::OwoColorize;
 use std::fmt::{self, Display, Formatter};
@@ -42,12 +42,27 @@ pub enum ShitzurewarderEventKind {
     NftUnstaked(NftUnstaked),
 }
 
+impl ShitzurewarderEventKind {
+    pub fn version(&self) -> &'static str {
+        match self {
+            ShitzurewarderEventKind::RewardSent(_) | ShitzurewarderEventKind::ScoreRecorded(_) => {
+                "1.1.0"
+            }
+            ShitzurewarderEventKind::NftStaked(_) | ShitzurewarderEventKind::NftUnstaked(_) => {
+                "1.0.0"
+            }
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct RewardSent {
     pub account_id: AccountId,
     pub amount: U128,
     pub token_id: Option<TokenId>,
+    pub reason: Option<String>,
+    pub memo: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -55,6 +70,8 @@ pub struct RewardSent {
 pub struct ScoreRecorded {
     pub token_id: TokenId,
     pub score: U128,
+    pub reason: Option<String>,
+    pub memo: Option<String>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
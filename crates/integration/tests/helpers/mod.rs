@@ -3,7 +3,7 @@ pub mod events;
 pub mod setup;
 pub mod view;
 
-use events::{ContractEvent, FtBurn, FtMint, ShitzurewarderEventKind, KNOWN_EVENT_KINDS};
+use events::{ContractEvent, FtBurn, FtMint, KNOWN_EVENT_KINDS, ShitzurewarderEventKind};
 use near_sdk::{json_types::U128, serde::Serialize};
 use near_workspaces::result::{ExecutionFinalResult, ExecutionResult, Value};
 use owo_colors::OwoColorize;
@@ -65,10 +65,11 @@ where
     });
     let mut expected = vec![];
     for event in events {
+        let version = event.version();
         let mut expected_event = serde_json::to_value(event)?;
         let ev = expected_event.as_object_mut().unwrap();
         ev.insert("standard".into(), "shitzurewarder".into());
-        ev.insert("version".into(), "1.0.0".into());
+        ev.insert("version".into(), version.into());
         expected.push(expected_event);
     }
     assert_eq!(
@@ -20,6 +20,7 @@ const SHITZU_NFT_WASM_FILEPATH: &str = "../../res//shitzu_nft.wasm";
 const REWARDER_WASM_FILEPATH: &str = "../../res/rewarder.wasm";
 const REF_FARM_WASM_FILEPATH: &str = "../../res/ref_farm.wasm";
 const MEMESEASON_WASM_FILEPATH: &str = "../../res/memeseason_integration_test.wasm";
+const BADGE_WASM_FILEPATH: &str = "../../res/badge.wasm";
 
 pub async fn setup_token(
     near: &Account,
@@ -82,6 +83,37 @@ pub async fn setup_nft(near: &Account) -> anyhow::Result<Contract> {
     Ok(contract)
 }
 
+pub async fn setup_badge(near: &Account, minter: &AccountId) -> anyhow::Result<Contract> {
+    let wasm = std::fs::read(BADGE_WASM_FILEPATH)?;
+
+    let contract = near
+        .create_subaccount("badge")
+        .initial_balance(NearToken::from_near(100))
+        .transact()
+        .await?
+        .into_result()?
+        .deploy(&wasm)
+        .await?
+        .into_result()?;
+
+    log_tx_result(
+        "Deployed badge contract",
+        contract
+            .call("new")
+            .args_json(json!(
+                {
+                    "minter": minter,
+                    "name": "Shitzu Badges",
+                    "symbol": "BADGE"
+                }
+            ))
+            .transact()
+            .await?,
+    )?;
+
+    Ok(contract)
+}
+
 pub async fn setup_rewarder(
     near: &Account,
     owner_id: &AccountId,
@@ -1,5 +1,5 @@
 use near_contract_standards::non_fungible_token::{Token, TokenId};
-use near_sdk::{json_types::U128, serde::Deserialize, AccountId};
+use near_sdk::{AccountId, json_types::U128, serde::Deserialize};
 use near_workspaces::Contract;
 
 use super::setup::FarmConfigs;
@@ -46,6 +46,19 @@ pub async fn nft_tokens_for_owner(
     Ok(res.json::<Vec<Token>>()?)
 }
 
+pub async fn badges_of(
+    contract: &Contract,
+    token_id: TokenId,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    let res = contract
+        .call("badges_of")
+        .args_json((token_id,))
+        .view()
+        .await?;
+
+    Ok(res.json::<_>()?)
+}
+
 pub async fn score_of(contract: &Contract, token_id: TokenId) -> anyhow::Result<U128> {
     let res = contract
         .call("score_of")
@@ -0,0 +1,96 @@
+use helpers::{
+    Ether, call,
+    setup::{SetupResult, setup, setup_badge},
+    view,
+};
+use near_sdk::{NearToken, json_types::U128};
+use serde_json::json;
+
+mod helpers;
+
+#[tokio::test]
+async fn test_badges_minted_once() -> anyhow::Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let SetupResult {
+        dao,
+        tgbot,
+        shitzu,
+        nft,
+        rewarder,
+        accounts,
+        ..
+    } = setup(&worker).await?;
+    let badge = setup_badge(&worker.root_account()?, rewarder.id()).await?;
+
+    let [alice, bob, ..] = &accounts[..] else {
+        anyhow::bail!("Expected at least 2 accounts, got {}", accounts.len())
+    };
+
+    let amount: U128 = Ether::from(1_000_000).into();
+    call::storage_deposit(&shitzu, alice, None, None).await?;
+    call::storage_deposit(&shitzu, alice, Some(rewarder.id()), None).await?;
+    call::mint_token(&shitzu, alice.id(), amount).await?;
+    call::transfer_token(shitzu.id(), alice, rewarder.id(), amount).await?;
+
+    call::set_badge_config(
+        &dao,
+        rewarder.id(),
+        badge.id(),
+        vec![Ether::from(100).into(), Ether::from(1_000).into()],
+    )
+    .await?;
+
+    let [alice_token, ..] = &call::mint_nft(alice, nft.id(), 1).await?[..] else {
+        anyhow::bail!("Expected at least 1 token, got 0")
+    };
+    call::stake(alice, rewarder.id(), nft.id(), &alice_token.token_id).await?;
+
+    call::send_rewards(
+        &tgbot,
+        rewarder.id(),
+        alice.id(),
+        Ether::from(100).into(),
+        "reward-1",
+    )
+    .await?;
+    call::send_rewards(
+        &tgbot,
+        rewarder.id(),
+        alice.id(),
+        Ether::from(100).into(),
+        "reward-2",
+    )
+    .await?;
+
+    assert_eq!(
+        view::badges_of(&rewarder, alice_token.token_id.clone()).await?,
+        vec![
+            json!({ "Milestone": { "threshold": U128::from(Ether::from(100)) } }),
+            json!("TopRank"),
+        ]
+    );
+    // badges are only minted once they are claimed
+    assert!(
+        view::nft_tokens_for_owner(&badge, alice.id())
+            .await?
+            .is_empty()
+    );
+    call::claim_badges(bob, rewarder.id(), &alice_token.token_id).await?;
+    let badges = view::nft_tokens_for_owner(&badge, alice.id()).await?;
+    assert_eq!(badges.len(), 2);
+
+    // badges are soulbound
+    assert!(
+        alice
+            .call(badge.id(), "nft_transfer")
+            .args_json((bob.id(), &badges[0].token_id, None::<u64>, None::<String>))
+            .deposit(NearToken::from_yoctonear(1))
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -1,11 +1,11 @@
 use anyhow::Ok;
 use helpers::{
-    assert_event_emits, assert_ft_burn_events, assert_ft_mint_events, call,
+    Ether, assert_event_emits, assert_ft_burn_events, assert_ft_mint_events, call,
     events::{
         FtBurn, FtMint, NftStaked, NftUnstaked, RewardSent, ScoreRecorded, ShitzurewarderEventKind,
     },
-    setup::{setup, SetupResult},
-    view, Ether,
+    setup::{SetupResult, setup},
+    view,
 };
 use near_sdk::json_types::U128;
 
@@ -35,32 +35,55 @@ async fn test_only_operator_can_send_shitzu() -> anyhow::Result<()> {
 
     assert_eq!(view::ft_balance_of(&shitzu, rewarder.id()).await?, amount);
 
-    assert!(bob
-        .call(rewarder.id(), "send_rewards")
-        .args_json((alice.id(), amount))
-        .max_gas()
-        .transact()
-        .await?
-        .into_result()
-        .is_err());
-
-    assert!(rewarder
-        .call("send_rewards")
-        .args_json((alice.id(), amount))
-        .max_gas()
-        .transact()
-        .await?
-        .into_result()
-        .is_err());
-
-    assert!(tgbot
-        .call(rewarder.id(), "send_rewards")
-        .args_json((alice.id(), amount))
-        .max_gas()
-        .transact()
-        .await?
-        .into_result()
-        .is_ok());
+    assert!(
+        bob.call(rewarder.id(), "send_rewards")
+            .args_json((
+                alice.id(),
+                amount,
+                "reward-1",
+                None::<String>,
+                None::<String>
+            ))
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()
+            .is_err()
+    );
+
+    assert!(
+        rewarder
+            .call("send_rewards")
+            .args_json((
+                alice.id(),
+                amount,
+                "reward-1",
+                None::<String>,
+                None::<String>
+            ))
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()
+            .is_err()
+    );
+
+    assert!(
+        tgbot
+            .call(rewarder.id(), "send_rewards")
+            .args_json((
+                alice.id(),
+                amount,
+                "reward-1",
+                None::<String>,
+                None::<String>
+            ))
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()
+            .is_ok()
+    );
 
     Ok(())
 }
@@ -109,7 +132,7 @@ async fn test_double_reward_nft_staker() -> anyhow::Result<()> {
 
     let reward: U128 = Ether::from(100).into();
 
-    let events = call::send_rewards(&tgbot, rewarder.id(), alice.id(), reward).await?;
+    let events = call::send_rewards(&tgbot, rewarder.id(), alice.id(), reward, "reward-1").await?;
     assert_eq!(
         view::ft_balance_of(&shitzu, alice.id()).await?,
         U128(reward.0 * 2)
@@ -121,10 +144,14 @@ async fn test_double_reward_nft_staker() -> anyhow::Result<()> {
                 account_id: alice.id().clone(),
                 amount: U128(reward.0 * 2),
                 token_id: Some(token_id.clone()),
+                reason: None,
+                memo: None,
             }),
             ShitzurewarderEventKind::ScoreRecorded(ScoreRecorded {
                 token_id,
                 score: U128(reward.0 * 2),
+                reason: None,
+                memo: None,
             }),
         ],
     )?;
@@ -141,7 +168,7 @@ async fn test_double_reward_nft_staker() -> anyhow::Result<()> {
     let supply = view::ft_total_supply(&rewarder).await?;
     assert_eq!(supply.0, reward.0 * 2);
 
-    let events = call::send_rewards(&tgbot, rewarder.id(), bob.id(), reward).await?;
+    let events = call::send_rewards(&tgbot, rewarder.id(), bob.id(), reward, "reward-2").await?;
     assert_eq!(view::ft_balance_of(&shitzu, bob.id()).await?, reward);
     assert_event_emits(
         &events,
@@ -149,6 +176,8 @@ async fn test_double_reward_nft_staker() -> anyhow::Result<()> {
             account_id: bob.id().clone(),
             amount: U128(reward.0),
             token_id: None,
+            reason: None,
+            memo: None,
         })],
     )?;
     assert_ft_mint_events(&events, vec![])?;
@@ -285,7 +314,7 @@ async fn test_nft_score_persist() -> anyhow::Result<()> {
         })],
     )?;
 
-    let events = call::send_rewards(&tgbot, rewarder.id(), alice.id(), reward).await?;
+    let events = call::send_rewards(&tgbot, rewarder.id(), alice.id(), reward, "reward-3").await?;
     assert_event_emits(
         &events,
         vec![
@@ -293,10 +322,14 @@ async fn test_nft_score_persist() -> anyhow::Result<()> {
                 account_id: alice.id().clone(),
                 amount: U128(reward.0 * 2),
                 token_id: Some(nft_token.token_id.clone()),
+                reason: None,
+                memo: None,
             }),
             ShitzurewarderEventKind::ScoreRecorded(ScoreRecorded {
                 token_id: nft_token.token_id.clone(),
                 score: U128(reward.0 * 2),
+                reason: None,
+                memo: None,
             }),
         ],
     )?;
@@ -361,7 +394,7 @@ async fn test_nft_score_persist() -> anyhow::Result<()> {
     let supply = view::ft_total_supply(&rewarder).await?;
     assert_eq!(supply.0, reward.0 * 2);
 
-    let events = call::send_rewards(&tgbot, rewarder.id(), bob.id(), reward).await?;
+    let events = call::send_rewards(&tgbot, rewarder.id(), bob.id(), reward, "reward-4").await?;
     assert_eq!(
         view::score_of(&rewarder, nft_token.token_id.clone()).await?,
         U128(reward.0 * 4)
@@ -373,10 +406,14 @@ async fn test_nft_score_persist() -> anyhow::Result<()> {
                 account_id: bob.id().clone(),
                 amount: U128(reward.0 * 2),
                 token_id: Some(nft_token.token_id.clone()),
+                reason: None,
+                memo: None,
             }),
             ShitzurewarderEventKind::ScoreRecorded(ScoreRecorded {
                 token_id: nft_token.token_id.clone(),
                 score: U128(reward.0 * 4),
+                reason: None,
+                memo: None,
             }),
         ],
     )?;
@@ -441,6 +478,8 @@ async fn test_donation_quadruple_score() -> anyhow::Result<()> {
         vec![ShitzurewarderEventKind::ScoreRecorded(ScoreRecorded {
             token_id: nft_token.token_id.clone(),
             score: U128(amount.0 * 4),
+            reason: None,
+            memo: None,
         })],
     )?;
     assert_ft_mint_events(
@@ -0,0 +1,213 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{env, json_types::U128, near};
+use primitive_types::U256;
+
+use crate::{Contract, ContractExt, event::RewarderEvent, reason::ScoreReason};
+
+pub const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct PointsAccrual {
+    pub points_per_day: U128,
+    /// Points accrued by a token staked since the first rate was set, times `DAY`
+    pub points_per_token: U128,
+    /// Last time `points_per_token` was updated, in nanoseconds
+    pub updated_at: u64,
+}
+
+impl PointsAccrual {
+    /// Points accrued by a token staked since the first rate was set, up to `now`.
+    pub(crate) fn points_per_token(&self, now: u64) -> u128 {
+        let elapsed = now.saturating_sub(self.updated_at);
+        (U256::from(self.points_per_token.0)
+            + U256::from(self.points_per_day.0) * U256::from(elapsed))
+        .as_u128()
+    }
+}
+
+#[near]
+impl Contract {
+    /// Settles the passively accrued staking points of a staked token into its score.
+    pub fn settle(&mut self, token_id: TokenId) -> U128 {
+        U128(self.internal_settle_points(&token_id))
+    }
+}
+
+impl Contract {
+    /// Points accrued by a token of the staked collection since the first rate was set.
+    fn internal_points_per_token(&self) -> u128 {
+        self.points_accruals.get(&self.nft).map_or(0, |accrual| {
+            accrual.points_per_token(env::block_timestamp())
+        })
+    }
+
+    /// Starts the accrual of a newly staked token.
+    pub(crate) fn internal_checkpoint_points(&mut self, token_id: &TokenId) {
+        self.points_checkpoints
+            .insert(token_id.clone(), self.internal_points_per_token());
+    }
+
+    pub(crate) fn internal_pending_points(&self, token_id: &TokenId) -> u128 {
+        if !self.token_id_to_account.contains_key(token_id) {
+            return 0;
+        }
+
+        // tokens staked before the accrual existed have no checkpoint yet
+        let checkpoint = *self.points_checkpoints.get(token_id).unwrap_or(&0);
+        (self.internal_points_per_token() - checkpoint) / DAY as u128
+    }
+
+    /// Settles accrued points into the score of a token. Needs to be called whenever a staked
+    /// token is touched, so that the ranking reflects the time it has been staked.
+    pub(crate) fn internal_settle_points(&mut self, token_id: &TokenId) -> u128 {
+        if !self.token_id_to_account.contains_key(token_id) {
+            return 0;
+        }
+
+        // banned tokens forfeit their points
+        if self.internal_is_banned(token_id) {
+            self.internal_checkpoint_points(token_id);
+            return 0;
+        }
+        let points = self.internal_pending_points(token_id);
+        if points == 0 {
+            return 0;
+        }
+        // keeps the fraction of a point that hasn't been settled
+        let checkpoint = *self.points_checkpoints.get(token_id).unwrap_or(&0);
+        self.points_checkpoints
+            .insert(token_id.clone(), checkpoint + points * DAY as u128);
+
+        self.internal_add_score(
+            token_id.clone(),
+            points,
+            &ScoreReason::code("staking_points"),
+        );
+
+        RewarderEvent::PointsAccrued {
+            token_id: token_id.clone(),
+            points: U128(points),
+        }
+        .emit();
+
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::{
+        fungible_token::FungibleTokenCore, non_fungible_token::core::NonFungibleTokenReceiver,
+    };
+    use near_sdk::{
+        AccountId,
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_points_accrue_while_staked() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_points_per_day(nft.clone(), U128(100));
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft)
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+
+        let context = VMContextBuilder::new().block_timestamp(DAY / 2).build();
+        testing_env!(context);
+        assert_eq!(contract.pending_points_of("1".into()), U128(50));
+        assert_eq!(contract.score_of("1".into()), U128(0));
+
+        // receiving score settles the accrued points first
+        contract.internal_record_score("1".into(), 10);
+        assert_eq!(contract.score_of("1".into()), U128(60));
+        assert_eq!(contract.pending_points_of("1".into()), U128(0));
+
+        let context = VMContextBuilder::new().block_timestamp(DAY).build();
+        testing_env!(context);
+        assert_eq!(contract.settle("1".into()), U128(50));
+        assert_eq!(contract.score_of("1".into()), U128(110));
+        assert_eq!(contract.ft_total_supply(), U128(110));
+
+        // unstaking settles, after that nothing accrues anymore
+        let context = VMContextBuilder::new().block_timestamp(2 * DAY).build();
+        testing_env!(context);
+        contract.on_unstake(alice, "1".into());
+        assert_eq!(contract.score_of("1".into()), U128(210));
+
+        let context = VMContextBuilder::new().block_timestamp(3 * DAY).build();
+        testing_env!(context);
+        assert_eq!(contract.settle("1".into()), U128(0));
+        assert_eq!(contract.score_of("1".into()), U128(210));
+    }
+
+    #[test]
+    fn test_points_rate_change() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        // staked while the rate is still 0
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(dao.clone())
+            .block_timestamp(DAY)
+            .build();
+        testing_env!(context);
+        contract.set_points_per_day(nft.clone(), U128(100));
+
+        // the new rate only applies from the time it was set
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(dao)
+            .block_timestamp(2 * DAY)
+            .build();
+        testing_env!(context);
+        assert_eq!(contract.pending_points_of("1".into()), U128(100));
+        contract.set_points_per_day(nft.clone(), U128(10));
+
+        let context = VMContextBuilder::new().block_timestamp(3 * DAY).build();
+        testing_env!(context);
+        assert_eq!(contract.settle("1".into()), U128(110));
+        assert_eq!(contract.score_of("1".into()), U128(110));
+    }
+
+    #[test]
+    #[should_panic(expected = "Collection can't be staked")]
+    fn test_panic_points_for_other_collection() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_points_per_day("other_nft".parse().unwrap(), U128(100));
+    }
+}
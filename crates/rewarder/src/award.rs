@@ -0,0 +1,159 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, json_types::U128, near, require};
+
+use crate::{Contract, ContractExt, event::RewarderEvent, reason::ScoreReason};
+
+#[near(serializers = [json])]
+#[derive(Clone)]
+pub enum ScoreTarget {
+    Token(TokenId),
+    /// Primary NFT of the account
+    Account(AccountId),
+}
+
+#[near(serializers = [json])]
+#[derive(Clone)]
+pub struct ScoreAward {
+    pub target: ScoreTarget,
+    pub amount: U128,
+    pub reason: String,
+    pub memo: Option<String>,
+}
+
+#[near]
+impl Contract {
+    /// Grants score for off-chain achievements, without paying reward tokens. Returns the
+    /// recorded score after multipliers.
+    pub fn award_score(
+        &mut self,
+        target: ScoreTarget,
+        amount: U128,
+        reason: String,
+        memo: Option<String>,
+    ) -> U128 {
+        self.require_operator();
+
+        U128(self.internal_award_score(ScoreAward {
+            target,
+            amount,
+            reason,
+            memo,
+        }))
+    }
+
+    pub fn award_scores(&mut self, awards: Vec<ScoreAward>) -> Vec<U128> {
+        self.require_operator();
+
+        awards
+            .into_iter()
+            .map(|award| U128(self.internal_award_score(award)))
+            .collect()
+    }
+}
+
+impl Contract {
+    fn internal_award_score(&mut self, award: ScoreAward) -> u128 {
+        let token_id = match award.target {
+            ScoreTarget::Token(token_id) => token_id,
+            ScoreTarget::Account(account_id) => self
+                .account_to_token_id
+                .get(&account_id)
+                .cloned()
+                .expect("Account has no NFT staked"),
+        };
+        require!(
+            self.token_id_to_account.contains_key(&token_id),
+            "Token is not staked"
+        );
+
+        let reason = ScoreReason::new(Some(award.reason), award.memo);
+        let amount =
+            self.internal_record_score_with_reason(token_id.clone(), award.amount.0, &reason);
+
+        RewarderEvent::ScoreAwarded {
+            token_id,
+            amount: U128(amount),
+            reason: reason.code.unwrap(),
+            memo: reason.memo,
+        }
+        .emit();
+
+        amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_award_scores() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator.clone(), vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(operator)
+            .build();
+        testing_env!(context);
+        contract.award_score(
+            ScoreTarget::Token("1".into()),
+            U128(100),
+            "contest".to_string(),
+            None,
+        );
+        contract.award_scores(vec![
+            ScoreAward {
+                target: ScoreTarget::Account(accounts(1)),
+                amount: U128(50),
+                reason: "contest".to_string(),
+                memo: Some("second place".to_string()),
+            },
+            ScoreAward {
+                target: ScoreTarget::Account(accounts(2)),
+                amount: U128(200),
+                reason: "contest".to_string(),
+                memo: Some("first place".to_string()),
+            },
+        ]);
+
+        assert_eq!(contract.score_of("1".into()), U128(150));
+        assert_eq!(contract.score_of("2".into()), U128(200));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only operator can call this method")]
+    fn test_panic_non_operator_awards_score() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .build();
+        testing_env!(context);
+        contract.award_score(
+            ScoreTarget::Token("1".into()),
+            U128(100),
+            "contest".to_string(),
+            None,
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use near_contract_standards::non_fungible_token::{TokenId, metadata::TokenMetadata};
+use near_sdk::{AccountId, NearToken, PromiseResult, env, ext_contract, json_types::U128, near};
+
+use crate::{
+    Contract, ContractExt, GAS_FOR_NFT_MINT, GAS_FOR_RESOLVE_TRANSFER, event::RewarderEvent,
+};
+
+/// Number of leaderboard ranks that earn the top rank badge
+pub const TOP_RANKS: usize = 10;
+/// Badges minted per claim, every mint needs (15 + 5) Tgas
+const BADGES_PER_CLAIM: usize = 10;
+
+#[ext_contract(badge_nft)]
+#[allow(dead_code)]
+trait BadgeNft {
+    fn nft_mint(
+        &mut self,
+        token_id: TokenId,
+        token_owner_id: AccountId,
+        token_metadata: TokenMetadata,
+    );
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct BadgeConfig {
+    /// Soulbound NFT contract that mints the badges
+    pub contract: AccountId,
+    /// Scores that earn a milestone badge
+    pub milestones: Vec<U128>,
+    /// Storage deposit attached to every mint, paid by the rewarder
+    pub mint_deposit: U128,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Badge {
+    Milestone { threshold: U128 },
+    FirstDonation,
+    TopRank,
+}
+
+impl Badge {
+    fn key(&self) -> String {
+        match self {
+            Badge::Milestone { threshold } => format!("milestone-{}", threshold.0),
+            Badge::FirstDonation => "first-donation".to_string(),
+            Badge::TopRank => format!("top-{TOP_RANKS}"),
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Badge::Milestone { threshold } => format!("Reached a score of {}", threshold.0),
+            Badge::FirstDonation => "Received a first donation".to_string(),
+            Badge::TopRank => format!("Ranked in the top {TOP_RANKS}"),
+        }
+    }
+}
+
+#[near]
+impl Contract {
+    /// Mints the badges a token earned to its staker. Anyone can call it, badges are only
+    /// minted once and the rewarder pays the storage deposit. Returns the number of mints.
+    pub fn claim_badges(&mut self, token_id: TokenId) -> u32 {
+        let config = self.badge_config.clone().expect("Badges are disabled");
+        let account_id = self
+            .token_id_to_account
+            .get(&token_id)
+            .cloned()
+            .expect("Token is not staked");
+
+        let mut pending = self.pending_badges.remove(&token_id).unwrap_or_default();
+        let remaining = pending.split_off(pending.len().min(BADGES_PER_CLAIM));
+        if !remaining.is_empty() {
+            self.pending_badges.insert(token_id.clone(), remaining);
+        }
+
+        for badge in &pending {
+            badge_nft::ext(config.contract.clone())
+                .with_static_gas(GAS_FOR_NFT_MINT)
+                .with_attached_deposit(NearToken::from_yoctonear(config.mint_deposit.0))
+                .nft_mint(
+                    format!("{token_id}:{}", badge.key()),
+                    account_id.clone(),
+                    TokenMetadata {
+                        title: Some(badge.title()),
+                        description: Some(format!("Earned by Shit Star #{token_id}")),
+                        media: None,
+                        media_hash: None,
+                        copies: Some(1),
+                        issued_at: Some(env::block_timestamp_ms().to_string()),
+                        expires_at: None,
+                        starts_at: None,
+                        updated_at: None,
+                        extra: None,
+                        reference: None,
+                        reference_hash: None,
+                    },
+                )
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                        .on_badge_minted(token_id.clone(), account_id.clone(), badge.clone()),
+                );
+        }
+
+        pending.len() as u32
+    }
+
+    #[private]
+    pub fn on_badge_minted(&mut self, token_id: TokenId, account_id: AccountId, badge: Badge) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            RewarderEvent::BadgeMinted {
+                token_id,
+                account_id,
+                badge,
+            }
+            .emit();
+            return;
+        }
+
+        // queue the badge again, so that the next claim retries the mint
+        let mut pending = self.pending_badges.remove(&token_id).unwrap_or_default();
+        pending.push(badge.clone());
+        self.pending_badges.insert(token_id.clone(), pending);
+
+        RewarderEvent::BadgeMintFailed {
+            token_id,
+            account_id,
+            badge,
+        }
+        .emit();
+    }
+}
+
+impl Contract {
+    /// Awards the milestone and top rank badges a token earned with its current score.
+    pub(crate) fn internal_check_score_badges(&mut self, token_id: &TokenId) {
+        let Some(config) = self.badge_config.clone() else {
+            return;
+        };
+
+        let score = *self.scores.get(token_id).unwrap_or(&0);
+        for threshold in config.milestones {
+            if threshold.0 <= score {
+                self.internal_award_badge(token_id, Badge::Milestone { threshold });
+            }
+        }
+
+        if self.internal_top_tokens(TOP_RANKS).contains(token_id) {
+            self.internal_award_badge(token_id, Badge::TopRank);
+        }
+    }
+
+    /// Queues a badge for minting, unless the token already earned it. Minting is left to
+    /// `claim_badges`, so that score changes don't depend on the badge contract.
+    pub(crate) fn internal_award_badge(&mut self, token_id: &TokenId, badge: Badge) {
+        if self.badge_config.is_none() || !self.token_id_to_account.contains_key(token_id) {
+            return;
+        }
+
+        let mut badges = self.badges.remove(token_id).unwrap_or_default();
+        let earned = badges.contains(&badge);
+        if !earned {
+            badges.push(badge.clone());
+        }
+        self.badges.insert(token_id.clone(), badges);
+        if earned {
+            return;
+        }
+
+        let mut pending = self.pending_badges.remove(token_id).unwrap_or_default();
+        pending.push(badge.clone());
+        self.pending_badges.insert(token_id.clone(), pending);
+
+        RewarderEvent::BadgeEarned {
+            token_id: token_id.clone(),
+            badge,
+        }
+        .emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_badges_are_awarded_once() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_badge_config(Some(BadgeConfig {
+            contract: "badges".parse().unwrap(),
+            milestones: vec![U128(100), U128(1_000)],
+            mint_deposit: U128(10_000_000_000_000_000_000_000),
+        }));
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 50);
+        assert_eq!(contract.badges_of("1".into()), vec![Badge::TopRank]);
+
+        contract.internal_record_score("1".into(), 100);
+        contract.internal_record_score("1".into(), 100);
+        contract.internal_record_donation(accounts(2), "1".into(), 10);
+        assert_eq!(
+            contract.badges_of("1".into()),
+            vec![
+                Badge::TopRank,
+                Badge::Milestone {
+                    threshold: U128(100)
+                },
+                Badge::FirstDonation,
+            ]
+        );
+
+        // nothing is minted until the badges are claimed
+        assert_eq!(contract.pending_badges_of("1".into()).len(), 3);
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(3))
+            .build();
+        testing_env!(context);
+        assert_eq!(contract.claim_badges("1".into()), 3);
+        assert!(contract.pending_badges_of("1".into()).is_empty());
+        assert_eq!(contract.claim_badges("1".into()), 0);
+    }
+}
@@ -0,0 +1,219 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, env, near};
+
+use crate::{Contract, ContractExt, event::RewarderEvent};
+
+#[near]
+impl Contract {
+    /// Bans an account from staking and earning score, and hides its staked NFT from the
+    /// leaderboard. The score is kept, so unbanning restores it.
+    pub fn ban_account(&mut self, account_id: AccountId, reason: Option<String>) {
+        self.require_moderator();
+
+        let token_id = self.account_to_token_id.get(&account_id).cloned();
+        self.internal_settle_rewards(token_id.as_ref());
+        if self.banned_accounts.insert(account_id.clone()) {
+            RewarderEvent::AccountBanned {
+                account_id,
+                moderator: env::predecessor_account_id(),
+                reason,
+            }
+            .emit();
+        }
+        self.internal_reset_rewards(token_id.as_ref());
+    }
+
+    pub fn unban_account(&mut self, account_id: AccountId) {
+        self.require_moderator();
+
+        let token_id = self.account_to_token_id.get(&account_id).cloned();
+        self.internal_settle_rewards(token_id.as_ref());
+        if self.banned_accounts.remove(&account_id) {
+            RewarderEvent::AccountUnbanned {
+                account_id,
+                moderator: env::predecessor_account_id(),
+            }
+            .emit();
+        }
+        self.internal_reset_rewards(token_id.as_ref());
+    }
+
+    /// Bans a token from staking and earning score, regardless of who owns it.
+    pub fn ban_token(&mut self, token_id: TokenId, reason: Option<String>) {
+        self.require_moderator();
+
+        self.internal_settle_rewards(Some(&token_id));
+        if self.banned_tokens.insert(token_id.clone()) {
+            RewarderEvent::TokenBanned {
+                token_id: token_id.clone(),
+                moderator: env::predecessor_account_id(),
+                reason,
+            }
+            .emit();
+        }
+        self.internal_reset_rewards(Some(&token_id));
+    }
+
+    pub fn unban_token(&mut self, token_id: TokenId) {
+        self.require_moderator();
+
+        self.internal_settle_rewards(Some(&token_id));
+        if self.banned_tokens.remove(&token_id) {
+            RewarderEvent::TokenUnbanned {
+                token_id: token_id.clone(),
+                moderator: env::predecessor_account_id(),
+            }
+            .emit();
+        }
+        self.internal_reset_rewards(Some(&token_id));
+    }
+}
+
+impl Contract {
+    /// Settles the rewards of a staked token before its ban changes, so that it keeps the
+    /// rewards from before the ban and forfeits the ones accrued while banned.
+    fn internal_settle_rewards(&mut self, token_id: Option<&TokenId>) {
+        if let Some(token_id) = token_id {
+            self.internal_settle_points(token_id);
+            self.internal_settle_stream(token_id);
+        }
+    }
+
+    fn internal_reset_rewards(&mut self, token_id: Option<&TokenId>) {
+        if let Some(token_id) = token_id {
+            self.internal_reset_stream_debt(token_id);
+        }
+    }
+
+    /// Whether a token is banned itself or staked by a banned account.
+    pub(crate) fn internal_is_banned(&self, token_id: &TokenId) -> bool {
+        self.banned_tokens.contains(token_id)
+            || self
+                .token_id_to_account
+                .get(token_id)
+                .is_some_and(|account_id| self.banned_accounts.contains(account_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::{
+        fungible_token::receiver::FungibleTokenReceiver,
+        non_fungible_token::core::NonFungibleTokenReceiver,
+    };
+    use near_sdk::{
+        PromiseOrValue,
+        json_types::U128,
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_banned_account_hidden_and_restored() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.internal_record_score("2".into(), 200);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.ban_account(accounts(1), Some("bot".to_string()));
+        assert!(contract.is_account_banned(accounts(1)));
+
+        assert_eq!(contract.internal_record_score("1".into(), 100), 0);
+        assert_eq!(contract.score_of("1".into()), U128(300));
+        let leaderboard = contract.get_leaderboard(None);
+        assert_eq!(leaderboard.len(), 1);
+        assert_eq!(leaderboard[0].1[0].0, "2".to_string());
+
+        contract.unban_account(accounts(1));
+        let leaderboard = contract.get_leaderboard(None);
+        assert_eq!(leaderboard.len(), 2);
+        assert_eq!(leaderboard[0].1[0].0, "1".to_string());
+    }
+
+    #[test]
+    fn test_banned_token_is_returned() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.ban_token("1".into(), None);
+        assert!(contract.is_token_banned("1".into()));
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        let PromiseOrValue::Value(returned) =
+            contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into())
+        else {
+            panic!("Expected a value");
+        };
+        assert!(returned);
+        assert_eq!(contract.staker_of("1".into()), None);
+    }
+
+    #[test]
+    fn test_banned_token_earns_nothing() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator,
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.internal_record_score("2".into(), 200);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.ban_token("1".into(), None);
+
+        // banned tokens don't win prizes or top rank badges
+        assert_eq!(contract.internal_top_tokens(1), vec!["2".to_string()]);
+
+        // donations to banned tokens are refunded
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        let PromiseOrValue::Value(refund) =
+            contract.ft_on_transfer(accounts(1), U128(100), "".into())
+        else {
+            panic!("Expected a value");
+        };
+        assert_eq!(refund, U128(100));
+        let PromiseOrValue::Value(refund) =
+            contract.ft_on_transfer(accounts(3), U128(100), r#"{"donate_to_token":"1"}"#.into())
+        else {
+            panic!("Expected a value");
+        };
+        assert_eq!(refund, U128(100));
+        assert_eq!(contract.score_of("1".into()), U128(300));
+    }
+}
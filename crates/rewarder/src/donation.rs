@@ -0,0 +1,285 @@
+use near_contract_standards::{fungible_token::core::ext_ft_core, non_fungible_token::TokenId};
+use near_sdk::{
+    AccountId, NearToken, Promise, PromiseResult, env, json_types::U128, near, require,
+};
+use primitive_types::U256;
+
+use crate::{
+    BPS_DENOMINATOR, Contract, ContractExt, GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER,
+    badge::Badge, event::RewarderEvent,
+};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct DonationSplit {
+    pub treasury: Option<AccountId>,
+    /// Share of every donation forwarded to the treasury, in basis points
+    pub treasury_bps: u16,
+    pub burn_account: Option<AccountId>,
+    /// Share of every donation sent to the burn account, in basis points
+    pub burn_bps: u16,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct DonationSplitTotals {
+    pub treasury: U128,
+    pub burned: U128,
+    /// Donations kept in the rewarder to fund future rewards
+    pub recycled: U128,
+}
+
+#[near(serializers = [json])]
+#[derive(Default)]
+pub struct DonationMsg {
+    /// Keep the donation of a sender without staked NFT until they stake one, instead of refunding it
+    #[serde(default)]
+    pub escrow: bool,
+    /// Boost a staked NFT other than the sender's primary NFT
+    pub donate_to_token: Option<TokenId>,
+    /// Fund the prize pool of a season instead of donating, only available to the owner
+    pub prize_pool: Option<u64>,
+    /// Distribute the amount among all stakers proportional to their score, only available to
+    /// the operator
+    #[serde(default)]
+    pub pro_rata_distribution: bool,
+    /// Top up the staking reward stream, only available to the owner
+    #[serde(default)]
+    pub fund_reward_stream: bool,
+}
+
+impl Contract {
+    pub(crate) fn internal_record_donation(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: u128,
+    ) {
+        let donated = *self.donation_amounts.get(&account_id).unwrap_or(&0);
+        let new_donated = (U256::from(donated) + U256::from(amount)).as_u128();
+        self.donation_amounts
+            .insert(account_id.clone(), new_donated);
+
+        // remove from old donor ranking
+        if donated > 0 {
+            let mut ranking = self
+                .donor_ranking
+                .get(&donated)
+                .cloned()
+                .unwrap_or_default();
+            ranking.retain(|x| x != &account_id);
+
+            if ranking.is_empty() {
+                self.donor_ranking.remove(&donated);
+            } else {
+                self.donor_ranking.insert(donated, ranking);
+            }
+        }
+
+        let mut ranking = self
+            .donor_ranking
+            .get(&new_donated)
+            .cloned()
+            .unwrap_or_default();
+        ranking.push(account_id.clone());
+        self.donor_ranking.insert(new_donated, ranking);
+
+        let received = *self.received_donations.get(&token_id).unwrap_or(&0);
+        self.received_donations.insert(
+            token_id.clone(),
+            (U256::from(received) + U256::from(amount)).as_u128(),
+        );
+        self.internal_award_badge(&token_id, Badge::FirstDonation);
+
+        self.total_donation = (U256::from(self.total_donation) + U256::from(amount)).as_u128();
+        self.internal_split_donation(amount);
+
+        RewarderEvent::Donated {
+            account_id,
+            token_id,
+            amount: U128(amount),
+            total: U128(new_donated),
+        }
+        .emit();
+    }
+
+    /// Returns whether the donation was accepted. Donations to banned tokens are refunded, as
+    /// they can't earn score.
+    pub(crate) fn internal_donate_to_token(
+        &mut self,
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: u128,
+    ) -> bool {
+        require!(
+            self.token_id_to_account.contains_key(&token_id),
+            "Token is not staked"
+        );
+        if self.internal_is_banned(&token_id) {
+            return false;
+        }
+
+        self.internal_record_score(token_id.clone(), amount * 4);
+        self.internal_record_donation(account_id.clone(), token_id.clone(), amount);
+
+        RewarderEvent::DonationReceived {
+            token_id: token_id.clone(),
+            donor: account_id,
+            amount: U128(amount),
+            total: U128(*self.received_donations.get(&token_id).unwrap_or(&0)),
+        }
+        .emit();
+
+        true
+    }
+
+    pub(crate) fn internal_escrow_donation(&mut self, account_id: AccountId, amount: u128) {
+        let escrowed = *self.escrowed_donations.get(&account_id).unwrap_or(&0);
+        let new_escrowed = (U256::from(escrowed) + U256::from(amount)).as_u128();
+        self.escrowed_donations
+            .insert(account_id.clone(), new_escrowed);
+        self.total_escrowed = (U256::from(self.total_escrowed) + U256::from(amount)).as_u128();
+
+        RewarderEvent::DonationEscrowed {
+            account_id,
+            amount: U128(amount),
+            total: U128(new_escrowed),
+        }
+        .emit();
+    }
+
+    pub(crate) fn internal_release_escrow(&mut self, account_id: AccountId, token_id: TokenId) {
+        let Some(amount) = self.escrowed_donations.remove(&account_id) else {
+            return;
+        };
+        self.total_escrowed -= amount;
+
+        RewarderEvent::EscrowReleased {
+            account_id: account_id.clone(),
+            token_id: token_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        self.internal_record_score(token_id.clone(), amount * 4);
+        self.internal_record_donation(account_id, token_id, amount);
+    }
+}
+
+#[near]
+impl Contract {
+    /// Refunds the escrowed donation of the caller, for donors who decided not to stake.
+    pub fn withdraw_escrow(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+
+        let amount = self
+            .escrowed_donations
+            .remove(&account_id)
+            .expect("No escrowed donation found");
+        self.total_escrowed -= amount;
+
+        RewarderEvent::EscrowWithdrawn {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        ext_ft_core::ext(self.reward_token.clone())
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .ft_transfer(
+                account_id.clone(),
+                U128(amount),
+                Some("Escrow refund".to_string()),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_escrow_withdrawn(account_id, U128(amount)),
+            )
+    }
+
+    #[private]
+    pub fn on_escrow_withdrawn(&mut self, account_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        // put it back into escrow, so that it can be withdrawn again
+        self.internal_escrow_donation(account_id.clone(), amount.0);
+
+        RewarderEvent::EscrowWithdrawFailed { account_id, amount }.emit();
+    }
+
+    #[private]
+    pub fn on_donation_forwarded(&mut self, receiver_id: AccountId, amount: U128, burn: bool) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        // tokens stay in the rewarder, so they are recycled into the reward pool
+        let totals = &mut self.donation_split_totals;
+        if burn {
+            totals.burned = U128(totals.burned.0 - amount.0);
+        } else {
+            totals.treasury = U128(totals.treasury.0 - amount.0);
+        }
+        totals.recycled = U128(totals.recycled.0 + amount.0);
+
+        RewarderEvent::DonationForwardFailed {
+            receiver_id,
+            amount,
+        }
+        .emit();
+    }
+}
+
+impl Contract {
+    fn internal_split_donation(&mut self, amount: u128) {
+        let split = self.donation_split.clone();
+        let treasury = (U256::from(amount) * U256::from(split.treasury_bps)
+            / U256::from(BPS_DENOMINATOR))
+        .as_u128();
+        let burned = (U256::from(amount) * U256::from(split.burn_bps)
+            / U256::from(BPS_DENOMINATOR))
+        .as_u128();
+        let recycled = amount - treasury - burned;
+
+        if let Some(receiver_id) = split.treasury.filter(|_| treasury > 0) {
+            self.internal_forward_donation(receiver_id, treasury, false);
+        }
+        if let Some(receiver_id) = split.burn_account.filter(|_| burned > 0) {
+            self.internal_forward_donation(receiver_id, burned, true);
+        }
+
+        let totals = &mut self.donation_split_totals;
+        totals.treasury = U128(totals.treasury.0 + treasury);
+        totals.burned = U128(totals.burned.0 + burned);
+        totals.recycled = U128(totals.recycled.0 + recycled);
+
+        RewarderEvent::DonationSplit {
+            amount: U128(amount),
+            treasury: U128(treasury),
+            burned: U128(burned),
+            recycled: U128(recycled),
+        }
+        .emit();
+    }
+
+    fn internal_forward_donation(&self, receiver_id: AccountId, amount: u128, burn: bool) {
+        let memo = if burn {
+            "Donation burn"
+        } else {
+            "Donation treasury share"
+        };
+        ext_ft_core::ext(self.reward_token.clone())
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .ft_transfer(receiver_id.clone(), U128(amount), Some(memo.to_string()))
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_donation_forwarded(receiver_id, U128(amount), burn),
+            );
+    }
+}
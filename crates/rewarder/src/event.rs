@@ -1,17 +1,30 @@
 use near_contract_standards::non_fungible_token::TokenId;
-use near_sdk::{json_types::U128, near_bindgen, AccountId};
+use near_sdk::{
+    AccountId,
+    json_types::{Base64VecU8, U128},
+    near_bindgen,
+};
+
+use crate::{badge::Badge, stream::StreamWeighting};
 
 #[near_bindgen(event_json(standard = "shitzurewarder"))]
 #[derive(Debug)]
 pub enum RewarderEvent {
-    #[event_version("1.0.0")]
+    #[event_version("1.1.0")]
     RewardSent {
         account_id: AccountId,
         amount: U128,
         token_id: Option<TokenId>,
+        reason: Option<String>,
+        memo: Option<String>,
+    },
+    #[event_version("1.1.0")]
+    ScoreRecorded {
+        token_id: TokenId,
+        score: U128,
+        reason: Option<String>,
+        memo: Option<String>,
     },
-    #[event_version("1.0.0")]
-    ScoreRecorded { token_id: TokenId, score: U128 },
     #[event_version("1.0.0")]
     NftStaked {
         account_id: AccountId,
@@ -22,4 +35,231 @@ pub enum RewarderEvent {
         account_id: AccountId,
         token_id: TokenId,
     },
+    #[event_version("1.0.0")]
+    ReferralRegistered {
+        account_id: AccountId,
+        referrer: AccountId,
+    },
+    #[event_version("1.0.0")]
+    ReferralCredited {
+        referrer: AccountId,
+        referee: AccountId,
+        token_id: TokenId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    Donated {
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+        total: U128,
+    },
+    #[event_version("1.0.0")]
+    DonationReceived {
+        token_id: TokenId,
+        donor: AccountId,
+        amount: U128,
+        total: U128,
+    },
+    #[event_version("1.0.0")]
+    DonationEscrowed {
+        account_id: AccountId,
+        amount: U128,
+        total: U128,
+    },
+    #[event_version("1.0.0")]
+    EscrowReleased {
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    EscrowWithdrawn { account_id: AccountId, amount: U128 },
+    #[event_version("1.0.0")]
+    EscrowWithdrawFailed { account_id: AccountId, amount: U128 },
+    #[event_version("1.0.0")]
+    DonationSplit {
+        amount: U128,
+        treasury: U128,
+        burned: U128,
+        recycled: U128,
+    },
+    #[event_version("1.0.0")]
+    DonationForwardFailed {
+        receiver_id: AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    TokensWithdrawn {
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    },
+    #[event_version("1.0.0")]
+    WithdrawFailed {
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    },
+    #[event_version("1.0.0")]
+    PrizePoolFunded {
+        season: u64,
+        amount: U128,
+        total: U128,
+    },
+    #[event_version("1.0.0")]
+    SeasonClosed { season: u64, winners: Vec<TokenId> },
+    #[event_version("1.0.0")]
+    PrizeSkipped {
+        season: u64,
+        rank: u32,
+        token_id: TokenId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    PrizePaid {
+        season: u64,
+        rank: u32,
+        token_id: TokenId,
+        account_id: AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    PrizePayoutFailed {
+        season: u64,
+        rank: u32,
+        token_id: TokenId,
+        account_id: AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    ProRataDistributionStarted {
+        id: u64,
+        amount: U128,
+        total_score: U128,
+    },
+    #[event_version("1.0.0")]
+    ProRataRewardSent {
+        id: u64,
+        account_id: AccountId,
+        token_id: TokenId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    ProRataRewardFailed {
+        id: u64,
+        account_id: AccountId,
+        amount: U128,
+    },
+    #[event_version("1.0.0")]
+    ProRataDistributionFinished {
+        id: u64,
+        distributed: U128,
+        dust: U128,
+    },
+    #[event_version("1.0.0")]
+    RewardStreamConfigured {
+        rate_per_second: U128,
+        weighting: StreamWeighting,
+    },
+    #[event_version("1.0.0")]
+    RewardStreamFunded { amount: U128, remaining: U128 },
+    #[event_version("1.0.0")]
+    StakingRewardsClaimed { account_id: AccountId, amount: U128 },
+    #[event_version("1.0.0")]
+    StakingRewardsClaimFailed { account_id: AccountId, amount: U128 },
+    #[event_version("1.0.0")]
+    VestingScheduleCreated {
+        account_id: AccountId,
+        amount: U128,
+        start: u64,
+        cliff_seconds: u64,
+        duration_seconds: u64,
+    },
+    #[event_version("1.0.0")]
+    VestedWithdrawn { account_id: AccountId, amount: U128 },
+    #[event_version("1.0.0")]
+    VestedWithdrawFailed { account_id: AccountId, amount: U128 },
+    #[event_version("1.0.0")]
+    PointsAccrued { token_id: TokenId, points: U128 },
+    #[event_version("1.0.0")]
+    StreakUpdated { token_id: TokenId, days: u32 },
+    #[event_version("1.0.0")]
+    LevelUp { token_id: TokenId, level: u32 },
+    #[event_version("1.0.0")]
+    BadgeEarned { token_id: TokenId, badge: Badge },
+    #[event_version("1.0.0")]
+    BadgeMinted {
+        token_id: TokenId,
+        account_id: AccountId,
+        badge: Badge,
+    },
+    #[event_version("1.0.0")]
+    BadgeMintFailed {
+        token_id: TokenId,
+        account_id: AccountId,
+        badge: Badge,
+    },
+    #[event_version("1.0.0")]
+    ItemRedeemed {
+        redemption_id: u64,
+        item_id: u64,
+        account_id: AccountId,
+        token_id: TokenId,
+        price: U128,
+    },
+    #[event_version("1.0.0")]
+    RedemptionFulfilled { redemption_id: u64 },
+    #[event_version("1.0.0")]
+    RedemptionFailed { redemption_id: u64 },
+    #[event_version("1.0.0")]
+    FtMetadataUpdated {
+        name: String,
+        symbol: String,
+        decimals: u8,
+        reference: Option<String>,
+        reference_hash: Option<Base64VecU8>,
+    },
+    #[event_version("1.0.0")]
+    ScoreAwarded {
+        token_id: TokenId,
+        amount: U128,
+        reason: String,
+        memo: Option<String>,
+    },
+    #[event_version("1.0.0")]
+    ScoreSlashed {
+        token_id: TokenId,
+        amount: U128,
+        score: U128,
+        moderator: AccountId,
+        reason: String,
+        memo: Option<String>,
+    },
+    #[event_version("1.0.0")]
+    AccountBanned {
+        account_id: AccountId,
+        moderator: AccountId,
+        reason: Option<String>,
+    },
+    #[event_version("1.0.0")]
+    AccountUnbanned {
+        account_id: AccountId,
+        moderator: AccountId,
+    },
+    #[event_version("1.0.0")]
+    TokenBanned {
+        token_id: TokenId,
+        moderator: AccountId,
+        reason: Option<String>,
+    },
+    #[event_version("1.0.0")]
+    TokenUnbanned {
+        token_id: TokenId,
+        moderator: AccountId,
+    },
+    #[event_version("1.0.0")]
+    ScoresImported { tokens: u32, score: U128 },
+    #[event_version("1.0.0")]
+    ImportSealed { total_score: U128 },
 }
@@ -1,20 +1,42 @@
-use crate::{Contract, ContractExt};
+use crate::{
+    BPS_DENOMINATOR, Contract, ContractExt, GAS_FOR_RESOLVE_TRANSFER, event::RewarderEvent,
+    reason::ScoreReason,
+};
 use near_contract_standards::fungible_token::{
+    FungibleTokenCore, FungibleTokenResolver,
+    events::{FtBurn, FtTransfer},
     metadata::{FungibleTokenMetadata, FungibleTokenMetadataProvider},
-    FungibleTokenCore,
+    receiver::ext_ft_receiver,
+};
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{
+    AccountId, PromiseOrValue, PromiseResult, assert_one_yocto, env, json_types::U128, near,
+    near_bindgen, require, serde_json,
 };
-use near_sdk::{json_types::U128, near_bindgen, AccountId, PromiseOrValue};
+use primitive_types::U256;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransferPolicy {
+    /// Shit Stars are soulbound
+    Disabled,
+    /// Transfers are allowed, a share of every transfer is burned
+    Fee { fee_bps: u16 },
+    /// Transfers are only allowed to the given receivers
+    Whitelist { receivers: Vec<AccountId> },
+}
 
 #[near_bindgen]
 impl FungibleTokenCore for Contract {
     #[payable]
-    #[allow(unused_variables)]
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
-        unimplemented!();
+        assert_one_yocto();
+
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer_score(&sender_id, &receiver_id, amount.0, memo.as_deref());
     }
 
     #[payable]
-    #[allow(unused_variables)]
     fn ft_transfer_call(
         &mut self,
         receiver_id: AccountId,
@@ -22,7 +44,20 @@ impl FungibleTokenCore for Contract {
         memo: Option<String>,
         msg: String,
     ) -> PromiseOrValue<U128> {
-        unimplemented!()
+        assert_one_yocto();
+
+        let sender_id = env::predecessor_account_id();
+        let received =
+            self.internal_transfer_score(&sender_id, &receiver_id, amount.0, memo.as_deref());
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .ft_on_transfer(sender_id.clone(), U128(received), msg)
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, U128(received)),
+            )
+            .into()
     }
 
     fn ft_total_supply(&self) -> U128 {
@@ -41,17 +76,294 @@ impl FungibleTokenCore for Contract {
     }
 }
 
+#[near_bindgen]
+impl FungibleTokenResolver for Contract {
+    /// Refunds the unused amount of `ft_transfer_call`, as long as both primary NFTs are still
+    /// staked. Otherwise the receiver keeps it.
+    #[private]
+    fn ft_resolve_transfer(
+        &mut self,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128,
+    ) -> U128 {
+        let unused = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<U128>(&value)
+                .map_or(amount.0, |unused| unused.0.min(amount.0)),
+            PromiseResult::Failed => amount.0,
+        };
+        let refund = unused.min(self.ft_balance_of(receiver_id.clone()).0);
+        if refund == 0 || !self.account_to_token_id.contains_key(&sender_id) {
+            return amount;
+        }
+
+        let receiver_token = self.account_to_token_id.get(&receiver_id).cloned().unwrap();
+        let sender_token = self.account_to_token_id.get(&sender_id).cloned().unwrap();
+        // score of banned tokens stays frozen
+        if self.internal_is_banned(&receiver_token) || self.internal_is_banned(&sender_token) {
+            return amount;
+        }
+        self.internal_move_score(
+            &receiver_token,
+            &sender_token,
+            refund,
+            0,
+            &ScoreReason::code("transfer_refund"),
+        );
+
+        FtTransfer {
+            old_owner_id: &receiver_id,
+            new_owner_id: &sender_id,
+            amount: U128(refund),
+            memo: Some("refund"),
+        }
+        .emit();
+
+        U128(amount.0 - refund)
+    }
+}
+
+impl Contract {
+    /// Moves score between the primary NFTs of two stakers according to the transfer policy.
+    /// Returns the amount the receiver got after fees.
+    fn internal_transfer_score(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128,
+        memo: Option<&str>,
+    ) -> u128 {
+        let fee_bps = match &self.transfer_policy {
+            TransferPolicy::Disabled => env::panic_str("Shit Stars transfers are disabled"),
+            TransferPolicy::Fee { fee_bps } => *fee_bps,
+            TransferPolicy::Whitelist { receivers } => {
+                require!(
+                    receivers.contains(receiver_id),
+                    "Receiver is not whitelisted for transfers"
+                );
+                0
+            }
+        };
+        require!(amount > 0, "The amount should be a positive number");
+        require!(
+            sender_id != receiver_id,
+            "Sender and receiver should be different"
+        );
+
+        let sender_token = self
+            .account_to_token_id
+            .get(sender_id)
+            .cloned()
+            .expect("Sender has no NFT staked");
+        let receiver_token = self
+            .account_to_token_id
+            .get(receiver_id)
+            .cloned()
+            .expect("Receiver has no NFT staked");
+
+        let fee =
+            (U256::from(amount) * U256::from(fee_bps) / U256::from(BPS_DENOMINATOR)).as_u128();
+        let received = amount - fee;
+        self.internal_move_score(
+            &sender_token,
+            &receiver_token,
+            amount,
+            fee,
+            &ScoreReason::new(Some("transfer".to_string()), memo.map(str::to_string)),
+        );
+
+        FtTransfer {
+            old_owner_id: sender_id,
+            new_owner_id: receiver_id,
+            amount: U128(received),
+            memo,
+        }
+        .emit();
+        if fee > 0 {
+            FtBurn {
+                owner_id: sender_id,
+                amount: U128(fee),
+                memo: Some("Transfer fee"),
+            }
+            .emit();
+        }
+
+        received
+    }
+
+    /// Moves `amount` of score from one staked token to another, burning `fee` of it.
+    fn internal_move_score(
+        &mut self,
+        from: &TokenId,
+        to: &TokenId,
+        amount: u128,
+        fee: u128,
+        reason: &ScoreReason,
+    ) {
+        require!(
+            !self.internal_is_banned(from) && !self.internal_is_banned(to),
+            "Banned tokens can't transfer score"
+        );
+        self.internal_settle_stream(from);
+        self.internal_settle_stream(to);
+
+        let from_score = *self.scores.get(from).unwrap_or(&0);
+        require!(
+            from_score >= amount,
+            "The account doesn't have enough balance"
+        );
+        self.internal_set_score(from, from_score, from_score - amount);
+
+        let to_score = *self.scores.get(to).unwrap_or(&0);
+        self.internal_set_score(to, to_score, to_score + amount - fee);
+
+        self.total_score -= fee;
+        self.total_staked_score -= fee;
+        self.internal_reset_stream_debt(from);
+        self.internal_reset_stream_debt(to);
+
+        for (token_id, score) in [(from, from_score - amount), (to, to_score + amount - fee)] {
+            RewarderEvent::ScoreRecorded {
+                token_id: token_id.clone(),
+                score: U128(score),
+                reason: reason.code.clone(),
+                memo: reason.memo.clone(),
+            }
+            .emit();
+        }
+    }
+}
+
 #[near_bindgen]
 impl FungibleTokenMetadataProvider for Contract {
     fn ft_metadata(&self) -> FungibleTokenMetadata {
-        FungibleTokenMetadata {
+        self.ft_metadata.get().clone().unwrap()
+    }
+}
+
+/// Metadata the rewarder is deployed with, until the owner changes it.
+pub(crate) fn default_ft_metadata() -> FungibleTokenMetadata {
+    FungibleTokenMetadata {
+        spec: "ft-1.0.0".to_string(),
+        name: "Shit Stars".to_string(),
+        symbol: "SHITSTARS".to_string(),
+        icon: Some("data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 256 256' xml:space='preserve' style='fill-rule:evenodd;clip-rule:evenodd;stroke-linejoin:round;stroke-miterlimit:2'%3E%3Cpath d='M128.48 96.54c2.86 4.29 1.27 8.25-.85 12.42a.998.998 0 0 1-.65.53l-6.6 1.93a.989.989 0 0 1-1.25-.82c-.78-6.04.393-11.617 3.52-16.73.213-.353.51-.607.89-.76a9.128 9.128 0 0 0 3.14-2.15c.48-.5 1.013-.567 1.6-.2.867.547 1.453 1.323 1.76 2.33a1.258 1.258 0 0 1-.45 1.36l-.86.64c-.54.407-.623.89-.25 1.45ZM100.71 96.26l1.31-.26a.733.733 0 0 1 .87.79c-.26 2.473-.433 4.933-.52 7.38-.12 3.34-1.8 7.59-5.55 8.21-.447.073-.783-.087-1.01-.48a107.04 107.04 0 0 0-3.69-6c-1.84-2.8-.76-5.59.9-8.11 1.34-2.05 3.32-2.59 5.2-3.9a.59.59 0 0 1 .88.27l.61 1.56a.883.883 0 0 0 1 .54ZM103.39 136.44c-1.713.687-2.89 1.73-3.53 3.13a1.24 1.24 0 0 1-.91.73c-.827.187-1.293-.123-1.4-.93-.18-1.47-.18-2.91.78-4.15 2.6-3.33 6.38-6.36 2.92-11-.97-1.3-1.41-2.79-1.56-4.39-.033-.353.11-.61.43-.77 1.987-.98 4.24-1.473 6.76-1.48 2.74 0 4.44 2.42 5.66 4.46a.507.507 0 0 1-.21.713l-.04.017c-2.51.95-3.91 2.71-5.33 4.96-1.28 2.02.91 3.51 2.16 4.76.267.273.39.6.37.98a8.8 8.8 0 0 0 .94 4.6c.221.437.305.928.24 1.41-.28 1.94-1.2 2.64-2.76 2.1a.902.902 0 0 1-.61-.78c-.14-1.747-1.24-3.187-3.3-4.32a.66.66 0 0 0-.61-.04Z' style='fill:%23030a20;fill-rule:nonzero' transform='matrix(4.93449 0 0 4.93449 -417.878 -445.1)'/%3E%3Cpath d='m9.5.5-.63 1.375L7.5 2.5l1.37.63.63 1.37.625-1.37L11.5 2.5l-1.375-.625M4.5 2 3.25 4.75.5 6l2.75 1.25L4.5 10l1.25-2.75L8.5 6 5.75 4.75M9.5 7.5l-.63 1.37-1.37.63 1.37.625.63 1.375.625-1.375L11.5 9.5l-1.375-.63' style='fill:%2331c891;fill-rule:nonzero' transform='rotate(15.623 -68.736 152.209) scale(4.91391)'/%3E%3Cpath d='m9.5.5-.63 1.375L7.5 2.5l1.37.63.63 1.37.625-1.37L11.5 2.5l-1.375-.625M4.5 2 3.25 4.75.5 6l2.75 1.25L4.5 10l1.25-2.75L8.5 6 5.75 4.75M9.5 7.5l-.63 1.37-1.37.63 1.37.625.63 1.375.625-1.375L11.5 9.5l-1.375-.63' style='fill:%2331c891;fill-rule:nonzero' transform='scale(4.118) rotate(16.721 -2.75 151.836)'/%3E%3C/svg%3E".to_string()),
+        reference: None,
+        reference_hash: None,
+        decimals: 18,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        NearToken,
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    fn setup_contract() -> Contract {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 1_000);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+
+        contract
+    }
+
+    #[test]
+    fn test_ft_transfer_with_fee() {
+        let mut contract = setup_contract();
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id("dao".parse().unwrap())
+            .build();
+        testing_env!(context);
+        contract.set_transfer_policy(TransferPolicy::Fee { fee_bps: 1_000 });
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build();
+        testing_env!(context);
+        contract.ft_transfer(accounts(2), U128(500), None);
+
+        assert_eq!(contract.ft_balance_of(accounts(1)), U128(500));
+        assert_eq!(contract.ft_balance_of(accounts(2)), U128(450));
+        assert_eq!(contract.ft_total_supply(), U128(950));
+        assert_eq!(
+            contract.get_leaderboard(None)[0],
+            (U128(500), vec![("1".to_string(), Some(accounts(1)))])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Shit Stars transfers are disabled")]
+    fn test_panic_ft_transfer_disabled() {
+        let mut contract = setup_contract();
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build();
+        testing_env!(context);
+        contract.ft_transfer(accounts(2), U128(500), None);
+    }
+
+    #[test]
+    fn test_set_ft_metadata() {
+        let mut contract = setup_contract();
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id("dao".parse().unwrap())
+            .build();
+        testing_env!(context);
+        contract.set_ft_metadata(FungibleTokenMetadata {
             spec: "ft-1.0.0".to_string(),
-            name: "Shit Stars".to_string(),
-            symbol: "SHITSTARS".to_string(),
-            icon: Some("data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 256 256' xml:space='preserve' style='fill-rule:evenodd;clip-rule:evenodd;stroke-linejoin:round;stroke-miterlimit:2'%3E%3Cpath d='M128.48 96.54c2.86 4.29 1.27 8.25-.85 12.42a.998.998 0 0 1-.65.53l-6.6 1.93a.989.989 0 0 1-1.25-.82c-.78-6.04.393-11.617 3.52-16.73.213-.353.51-.607.89-.76a9.128 9.128 0 0 0 3.14-2.15c.48-.5 1.013-.567 1.6-.2.867.547 1.453 1.323 1.76 2.33a1.258 1.258 0 0 1-.45 1.36l-.86.64c-.54.407-.623.89-.25 1.45ZM100.71 96.26l1.31-.26a.733.733 0 0 1 .87.79c-.26 2.473-.433 4.933-.52 7.38-.12 3.34-1.8 7.59-5.55 8.21-.447.073-.783-.087-1.01-.48a107.04 107.04 0 0 0-3.69-6c-1.84-2.8-.76-5.59.9-8.11 1.34-2.05 3.32-2.59 5.2-3.9a.59.59 0 0 1 .88.27l.61 1.56a.883.883 0 0 0 1 .54ZM103.39 136.44c-1.713.687-2.89 1.73-3.53 3.13a1.24 1.24 0 0 1-.91.73c-.827.187-1.293-.123-1.4-.93-.18-1.47-.18-2.91.78-4.15 2.6-3.33 6.38-6.36 2.92-11-.97-1.3-1.41-2.79-1.56-4.39-.033-.353.11-.61.43-.77 1.987-.98 4.24-1.473 6.76-1.48 2.74 0 4.44 2.42 5.66 4.46a.507.507 0 0 1-.21.713l-.04.017c-2.51.95-3.91 2.71-5.33 4.96-1.28 2.02.91 3.51 2.16 4.76.267.273.39.6.37.98a8.8 8.8 0 0 0 .94 4.6c.221.437.305.928.24 1.41-.28 1.94-1.2 2.64-2.76 2.1a.902.902 0 0 1-.61-.78c-.14-1.747-1.24-3.187-3.3-4.32a.66.66 0 0 0-.61-.04Z' style='fill:%23030a20;fill-rule:nonzero' transform='matrix(4.93449 0 0 4.93449 -417.878 -445.1)'/%3E%3Cpath d='m9.5.5-.63 1.375L7.5 2.5l1.37.63.63 1.37.625-1.37L11.5 2.5l-1.375-.625M4.5 2 3.25 4.75.5 6l2.75 1.25L4.5 10l1.25-2.75L8.5 6 5.75 4.75M9.5 7.5l-.63 1.37-1.37.63 1.37.625.63 1.375.625-1.375L11.5 9.5l-1.375-.63' style='fill:%2331c891;fill-rule:nonzero' transform='rotate(15.623 -68.736 152.209) scale(4.91391)'/%3E%3Cpath d='m9.5.5-.63 1.375L7.5 2.5l1.37.63.63 1.37.625-1.37L11.5 2.5l-1.375-.625M4.5 2 3.25 4.75.5 6l2.75 1.25L4.5 10l1.25-2.75L8.5 6 5.75 4.75M9.5 7.5l-.63 1.37-1.37.63 1.37.625.63 1.375.625-1.375L11.5 9.5l-1.375-.63' style='fill:%2331c891;fill-rule:nonzero' transform='scale(4.118) rotate(16.721 -2.75 151.836)'/%3E%3C/svg%3E".to_string()),
-            reference: None,
-            reference_hash: None,
+            name: "Season Stars".to_string(),
+            symbol: "STARS".to_string(),
+            icon: None,
+            reference: Some("https://example.com/stars.json".to_string()),
+            reference_hash: Some(vec![0; 32].into()),
             decimals: 18,
-        }
+        });
+
+        let metadata = contract.ft_metadata();
+        assert_eq!(metadata.symbol, "STARS");
+        assert_eq!(
+            metadata.reference.as_deref(),
+            Some("https://example.com/stars.json")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Banned tokens can't transfer score")]
+    fn test_panic_ft_transfer_from_banned_token() {
+        let mut contract = setup_contract();
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id("dao".parse().unwrap())
+            .build();
+        testing_env!(context);
+        contract.set_transfer_policy(TransferPolicy::Fee { fee_bps: 0 });
+        contract.ban_token("1".into(), None);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build();
+        testing_env!(context);
+        contract.ft_transfer(accounts(2), U128(500), None);
     }
 }
@@ -0,0 +1,131 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, json_types::U128, near, require};
+
+use crate::{Contract, ContractExt, event::RewarderEvent};
+
+#[near]
+impl Contract {
+    /// Carries over scores exported from a previous deployment, in chunks. Tokens with a staker
+    /// are staked for that staker once their NFT is transferred to this contract, until then
+    /// their score is unstaked. Only available until the import is sealed.
+    pub fn import_scores(&mut self, scores: Vec<(TokenId, U128, Option<AccountId>)>) {
+        self.require_owner();
+        require!(!self.import_sealed, "Import is sealed");
+
+        let mut total = 0;
+        for (token_id, score, staker) in &scores {
+            require!(
+                !self.scores.contains_key(token_id)
+                    && !self.token_id_to_account.contains_key(token_id)
+                    && !self.imported_stakers.contains_key(token_id),
+                "Token is already imported"
+            );
+
+            self.internal_set_score(token_id, 0, score.0);
+            self.total_score += score.0;
+            if let Some(account_id) = staker {
+                self.imported_stakers
+                    .insert(token_id.clone(), account_id.clone());
+            }
+            total += score.0;
+        }
+
+        RewarderEvent::ScoresImported {
+            tokens: scores.len() as u32,
+            score: U128(total),
+        }
+        .emit();
+    }
+
+    /// Ends the import phase for good.
+    pub fn seal_import(&mut self) {
+        self.require_owner();
+        require!(!self.import_sealed, "Import is sealed");
+
+        self.import_sealed = true;
+
+        RewarderEvent::ImportSealed {
+            total_score: U128(self.total_score),
+        }
+        .emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts, get_logs},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_import_scores() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(dao.clone())
+            .build();
+        testing_env!(context);
+        contract.import_scores(vec![
+            ("1".into(), U128(300), Some(accounts(1))),
+            ("2".into(), U128(200), None),
+        ]);
+        contract.import_scores(vec![("3".into(), U128(100), Some(accounts(3)))]);
+
+        // nothing is staked before the NFTs arrive
+        assert_eq!(contract.staker_of("1".into()), None);
+        assert_eq!(contract.imported_staker_of("1".into()), Some(accounts(1)));
+        assert_eq!(contract.score_of("2".into()), U128(200));
+        assert_eq!(contract.get_leaderboard(None).len(), 3);
+        assert_eq!(contract.total_score, 600);
+        assert_eq!(contract.total_staked_score, 0);
+        assert_eq!(contract.total_nft_staked, 0);
+
+        // the owner hands over NFT 1, NFT 3 is staked by someone else than the imported staker
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(dao.clone(), dao.clone(), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(2), accounts(2), "3".into(), "".into());
+
+        assert!(
+            get_logs()
+                .iter()
+                .any(|log| log.contains(r#""event":"ft_mint""#)
+                    && log.contains(&format!(r#""owner_id":"{}""#, accounts(1))))
+        );
+        assert_eq!(contract.staker_of("1".into()), Some(accounts(1)));
+        assert_eq!(contract.staker_of("3".into()), Some(accounts(2)));
+        assert_eq!(contract.imported_staker_of("3".into()), None);
+        assert_eq!(contract.total_staked_score, 400);
+        assert_eq!(contract.total_nft_staked, 2);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.seal_import();
+        assert!(contract.is_import_sealed());
+    }
+
+    #[test]
+    #[should_panic(expected = "Import is sealed")]
+    fn test_panic_import_after_seal() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.seal_import();
+        contract.import_scores(vec![("1".into(), U128(300), None)]);
+    }
+}
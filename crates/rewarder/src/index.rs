@@ -0,0 +1,183 @@
+use std::ops::Bound;
+
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, near, require};
+
+use crate::{Contract, ContractExt};
+
+#[near]
+impl Contract {
+    /// Indexes stakers the backfill couldn't find, because their token had no score yet. Anyone
+    /// can call it, as only accounts with a staked NFT are indexed. Returns the number of newly
+    /// indexed stakers.
+    pub fn index_stakers(&mut self, account_ids: Vec<AccountId>) -> u32 {
+        let mut indexed = 0;
+        for account_id in account_ids {
+            if self.account_to_token_id.contains_key(&account_id)
+                && self.internal_index_staker(&account_id)
+            {
+                indexed += 1;
+            }
+        }
+        indexed
+    }
+
+    /// Indexes the scored tokens and stakers of the ranking that existed before the migration,
+    /// from the lowest score up. Anyone can call it until it is complete, as tokens touched in
+    /// the meantime are indexed on the way. Tokens of the same score are indexed together, so a
+    /// call can index more than `limit` tokens. Returns whether the backfill is complete.
+    pub fn backfill_scored_tokens(&mut self, limit: Option<u32>) -> bool {
+        require!(!self.ranking_backfill_done, "Backfill already complete");
+
+        let limit = limit.unwrap_or(50) as usize;
+        let from = self
+            .ranking_backfill_cursor
+            .map_or(Bound::Unbounded, Bound::Excluded);
+        let mut entries: Vec<(u128, Vec<TokenId>)> = vec![];
+        let mut tokens = 0;
+        for (score, token_ids) in self.ranking.range((from, Bound::Unbounded)) {
+            if tokens >= limit {
+                break;
+            }
+            tokens += token_ids.len();
+            entries.push((*score, token_ids.clone()));
+        }
+
+        let highest = self.ranking.iter().next_back().map(|(score, _)| *score);
+        for (score, token_ids) in entries {
+            for token_id in &token_ids {
+                self.internal_backfill_scored_token(token_id);
+            }
+            self.ranking_backfill_cursor = Some(score);
+        }
+        self.ranking_backfill_done = highest.is_none() || self.ranking_backfill_cursor == highest;
+
+        self.ranking_backfill_done
+    }
+}
+
+impl Contract {
+    /// Appends a staker to the index, so that existing entries keep their position.
+    pub(crate) fn internal_index_staker(&mut self, account_id: &AccountId) -> bool {
+        if self.staker_ids.contains_key(account_id) {
+            return false;
+        }
+
+        let id = self.staker_nonce;
+        self.staker_nonce += 1;
+        self.stakers.insert(id, account_id.clone());
+        self.staker_ids.insert(account_id.clone(), id);
+        true
+    }
+
+    pub(crate) fn internal_unindex_staker(&mut self, account_id: &AccountId) {
+        if let Some(id) = self.staker_ids.remove(account_id) {
+            self.stakers.remove(&id);
+        }
+    }
+
+    /// Indexes a token scored before the migration, while the backfill is running. Needs to be
+    /// called before the score or the stake of a token changes.
+    pub(crate) fn internal_backfill_scored_token(&mut self, token_id: &TokenId) {
+        if self.ranking_backfill_done || self.scored_token_set.contains(token_id) {
+            return;
+        }
+        let score = *self.scores.get(token_id).unwrap_or(&0);
+        if score == 0 {
+            return;
+        }
+
+        self.internal_index_scored_token(token_id);
+        self.scored_token_count += 1;
+        if let Some(account_id) = self.token_id_to_account.get(token_id).cloned() {
+            self.total_staked_score += score;
+            self.internal_index_staker(&account_id);
+        }
+    }
+
+    /// Appends a token to the scored token index when it gets its first score. Tokens are never
+    /// removed, so that indexes stay stable.
+    pub(crate) fn internal_index_scored_token(&mut self, token_id: &TokenId) {
+        if self.scored_token_set.insert(token_id.clone()) {
+            self.scored_tokens.push(token_id.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        json_types::U128,
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_enumerate_stakers_and_scored_tokens() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(3), "3".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.internal_record_score("2".into(), 200);
+
+        // unstaking doesn't move the remaining stakers
+        contract.on_unstake(accounts(1), "1".into());
+        assert_eq!(
+            contract.get_stakers(Some(1), Some(2)),
+            vec![
+                (1, accounts(2), "2".to_string()),
+                (2, accounts(3), "3".to_string())
+            ]
+        );
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        assert_eq!(
+            contract.get_stakers(Some(3), None),
+            vec![(3, accounts(1), "1".to_string())]
+        );
+
+        contract.internal_remove_score("2".into(), 200, &Default::default());
+        assert_eq!(
+            contract.get_scored_tokens(None, None),
+            vec![
+                (0, "1".to_string(), U128(300)),
+                (1, "2".to_string(), U128(0))
+            ]
+        );
+        assert_eq!(contract.get_stats().scored_tokens, 1);
+    }
+
+    #[test]
+    fn test_index_stakers() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        // staked before the index existed
+        contract.internal_unindex_staker(&accounts(1));
+
+        assert_eq!(contract.index_stakers(vec![accounts(1), accounts(2)]), 1);
+        assert_eq!(contract.index_stakers(vec![accounts(1)]), 0);
+        assert_eq!(
+            contract.get_stakers(None, None),
+            vec![(1, accounts(1), "1".to_string())]
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{json_types::U128, near};
+use primitive_types::U256;
+
+use crate::{BPS_DENOMINATOR, Contract, event::RewarderEvent};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Level {
+    /// Score needed to reach this level
+    pub threshold: U128,
+    /// Extra multiplier on `send_rewards` in basis points, 10_000 being 1x
+    pub reward_multiplier_bps: Option<u16>,
+}
+
+impl Contract {
+    /// Number of level thresholds the score of a token has reached.
+    pub(crate) fn internal_level_of(&self, token_id: &TokenId) -> u32 {
+        self.internal_level_for(*self.scores.get(token_id).unwrap_or(&0))
+    }
+
+    fn internal_level_for(&self, score: u128) -> u32 {
+        self.levels
+            .iter()
+            .take_while(|level| level.threshold.0 <= score)
+            .count() as u32
+    }
+
+    /// Emits `LevelUp` if the score of a token raised from `previous_score` reached a new level.
+    pub(crate) fn internal_emit_level_up(&self, token_id: &TokenId, previous_score: u128) {
+        let level = self.internal_level_of(token_id);
+        if level > self.internal_level_for(previous_score) {
+            RewarderEvent::LevelUp {
+                token_id: token_id.clone(),
+                level,
+            }
+            .emit();
+        }
+    }
+
+    /// Applies the reward multiplier perk of the token's level.
+    pub(crate) fn internal_apply_level_perk(&self, token_id: &TokenId, amount: u128) -> u128 {
+        let level = self.internal_level_of(token_id);
+        let Some(multiplier_bps) = level
+            .checked_sub(1)
+            .and_then(|index| self.levels[index as usize].reward_multiplier_bps)
+        else {
+            return amount;
+        };
+
+        (U256::from(amount) * U256::from(multiplier_bps) / U256::from(BPS_DENOMINATOR)).as_u128()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        AccountId,
+        test_utils::{VMContextBuilder, accounts, get_logs},
+        testing_env,
+    };
+
+    use super::*;
+    use crate::accrual::DAY;
+
+    #[test]
+    fn test_level_up_and_reward_perk() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator.clone(),
+            vec![],
+            reward_token,
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_levels(vec![
+            Level {
+                threshold: U128(100),
+                reward_multiplier_bps: None,
+            },
+            Level {
+                threshold: U128(1_000),
+                reward_multiplier_bps: Some(15_000),
+            },
+        ]);
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 50);
+        assert_eq!(contract.level_of("1".into()), 0);
+
+        contract.internal_record_score("1".into(), 1_000);
+        assert_eq!(contract.level_of("1".into()), 2);
+        assert!(
+            get_logs()
+                .iter()
+                .any(|log| log.contains(r#""event":"level_up","data":{"token_id":"1","level":2}"#))
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(operator)
+            .build();
+        testing_env!(context);
+        contract.send_rewards(alice, U128(100), "reward-1".to_string(), None, None);
+        assert!(get_logs()[0].contains(r#""amount":"300""#));
+    }
+
+    #[test]
+    fn test_level_up_from_settled_points() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(dao)
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.set_levels(vec![Level {
+            threshold: U128(100),
+            reward_multiplier_bps: None,
+        }]);
+        contract.set_points_per_day(nft.clone(), U128(100));
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft)
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+
+        let context = VMContextBuilder::new().block_timestamp(DAY).build();
+        testing_env!(context);
+        contract.settle("1".into());
+        assert!(
+            get_logs()
+                .iter()
+                .any(|log| log.contains(r#""event":"level_up","data":{"token_id":"1","level":1}"#))
+        );
+    }
+}
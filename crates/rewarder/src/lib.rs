@@ -1,27 +1,73 @@
+mod accrual;
+mod award;
+mod badge;
+mod ban;
+mod donation;
 mod event;
 mod ft;
+mod import;
+mod index;
+mod level;
+mod migration;
 mod nft;
 mod owner;
+mod prize;
+mod pro_rata;
+mod reason;
+mod referral;
+mod reward;
+mod shop;
+mod slash;
+mod stats;
+mod storage;
+mod streak;
+mod stream;
 mod token_receiver;
+mod vesting;
 mod view;
 
+use accrual::PointsAccrual;
+use badge::{Badge, BadgeConfig};
+use donation::{DonationSplit, DonationSplitTotals};
 use event::RewarderEvent;
+use ft::{TransferPolicy, default_ft_metadata};
+use level::Level;
 use near_contract_standards::{
-    fungible_token::{core::ext_ft_core, events::FtMint},
+    fungible_token::{
+        core::ext_ft_core,
+        events::{FtBurn, FtMint},
+        metadata::FungibleTokenMetadata,
+    },
     non_fungible_token::TokenId,
 };
 use near_sdk::{
-    AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, Promise,
+    AccountId, BorshStorageKey, Gas, NearToken, PanicOnDefault, PromiseOrValue, PromiseResult,
     borsh::BorshSerialize,
     env,
     json_types::U128,
     near, require,
-    store::{LookupMap, TreeMap},
+    store::{LazyOption, LookupMap, LookupSet, TreeMap, Vector},
 };
 use primitive_types::U256;
+use prize::PrizePool;
+use pro_rata::ProRataDistribution;
+use reason::ScoreReason;
+use referral::{Referral, ReferralConfig, ReferrerStats};
+use reward::{REWARD_ID_RETENTION, RewardStatus};
+use shop::{Redemption, ShopItem};
+use slash::SlashRecord;
+use streak::{Streak, StreakTier};
+use stream::RewardStream;
+use vesting::{VestingConfig, VestingSchedule};
 
 pub const GAS_FOR_FT_TRANSFER: Gas = Gas::from_tgas(8);
 pub const GAS_FOR_NFT_TRANSFER: Gas = Gas::from_tgas(10);
+pub const GAS_FOR_NFT_MINT: Gas = Gas::from_tgas(15);
+pub const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_tgas(5);
+pub const GAS_FOR_FT_BALANCE_OF: Gas = Gas::from_tgas(5);
+pub const GAS_FOR_MIGRATE: Gas = Gas::from_tgas(10);
+
+pub const BPS_DENOMINATOR: u128 = 10_000;
 
 // Define the contract structure
 #[near(contract_state)]
@@ -39,10 +85,97 @@ pub struct Contract {
     total_nft_staked: u128,
 
     total_score: u128,
+    /// Score of all currently staked tokens
+    total_staked_score: u128,
     total_distribute: u128,
     total_donation: u128,
     scores: LookupMap<TokenId, u128>,
     ranking: TreeMap<u128, Vec<TokenId>>,
+
+    donation_amounts: LookupMap<AccountId, u128>,
+    donor_ranking: TreeMap<u128, Vec<AccountId>>,
+    received_donations: LookupMap<TokenId, u128>,
+    escrowed_donations: LookupMap<AccountId, u128>,
+    total_escrowed: u128,
+    donation_split: DonationSplit,
+    donation_split_totals: DonationSplitTotals,
+
+    prize_pools: LookupMap<u64, PrizePool>,
+    /// Funded prizes that have been neither paid nor skipped yet
+    total_unpaid_prizes: u128,
+
+    pro_rata_distributions: LookupMap<u64, ProRataDistribution>,
+    pro_rata_distribution_count: u64,
+    pro_rata_last_paid: LookupMap<TokenId, u64>,
+    /// Staked score of tokens that changed during the active distribution, with its id
+    pro_rata_snapshots: LookupMap<TokenId, (u64, u128)>,
+
+    reward_stream: Option<RewardStream>,
+    stream_debts: LookupMap<TokenId, u128>,
+    stream_pending: LookupMap<AccountId, u128>,
+    /// Streamed rewards that have not been claimed yet
+    stream_unclaimed: u128,
+
+    vesting_config: Option<VestingConfig>,
+    vesting_schedules: LookupMap<AccountId, Vec<VestingSchedule>>,
+    total_vesting: u128,
+
+    /// Points accrual of each NFT collection
+    points_accruals: LookupMap<AccountId, PointsAccrual>,
+    /// Value of `points_per_token` when the points of a token were last settled
+    points_checkpoints: LookupMap<TokenId, u128>,
+
+    streak_tiers: Vec<StreakTier>,
+    streaks: LookupMap<TokenId, Streak>,
+
+    levels: Vec<Level>,
+
+    badge_config: Option<BadgeConfig>,
+    badges: LookupMap<TokenId, Vec<Badge>>,
+    /// Earned badges that have not been minted yet
+    pending_badges: LookupMap<TokenId, Vec<Badge>>,
+
+    shop_items: LookupMap<u64, ShopItem>,
+    shop_item_count: u64,
+    redemptions: LookupMap<u64, Redemption>,
+    redemption_count: u64,
+    account_redemptions: LookupMap<AccountId, Vec<u64>>,
+
+    transfer_policy: TransferPolicy,
+    /// Kept out of the contract state, as the icon alone is several kilobytes
+    ft_metadata: LazyOption<FungibleTokenMetadata>,
+
+    reward_statuses: LookupMap<String, RewardStatus>,
+    /// Ring buffer of the most recent reward ids, used to expire old ones
+    reward_ids: Vector<String>,
+    reward_id_cursor: u32,
+
+    moderators: Vec<AccountId>,
+    slashes: Vector<SlashRecord>,
+    banned_accounts: LookupSet<AccountId>,
+    banned_tokens: LookupSet<TokenId>,
+
+    /// Stakers keyed by the order they staked in, as the stake mappings can't be iterated
+    stakers: TreeMap<u64, AccountId>,
+    staker_ids: LookupMap<AccountId, u64>,
+    staker_nonce: u64,
+    /// Tokens in the order they were first scored
+    scored_tokens: Vector<TokenId>,
+    scored_token_set: LookupSet<TokenId>,
+    /// Number of tokens with a non-zero score
+    scored_token_count: u64,
+    /// Highest score whose ranking entry has been backfilled into the indexes after the migration
+    ranking_backfill_cursor: Option<u128>,
+    ranking_backfill_done: bool,
+
+    /// Whether `import_scores` is closed for good
+    import_sealed: bool,
+    /// Imported stakers, whose stake is activated once their NFT arrives
+    imported_stakers: LookupMap<TokenId, AccountId>,
+
+    referral_config: ReferralConfig,
+    referrals: LookupMap<AccountId, Referral>,
+    referrer_stats: LookupMap<AccountId, ReferrerStats>,
 }
 
 #[derive(BorshStorageKey, BorshSerialize)]
@@ -55,6 +188,36 @@ pub enum StorageKey {
     DonationAmounts,
     DonorRanking,
     RefMemeSeasonRewardCheckpoint,
+    Referrals,
+    ReferrerStats,
+    EscrowedDonations,
+    ReceivedDonations,
+    PrizePools,
+    ProRataDistributions,
+    ProRataLastPaid,
+    StreamDebts,
+    StreamPending,
+    VestingSchedules,
+    PointsAccruals,
+    PointsCheckpoints,
+    Streaks,
+    Badges,
+    ShopItems,
+    Redemptions,
+    AccountRedemptions,
+    RewardStatuses,
+    RewardIds,
+    Slashes,
+    BannedAccounts,
+    BannedTokens,
+    Stakers,
+    ScoredTokens,
+    StakerIds,
+    ScoredTokenSet,
+    ProRataSnapshots,
+    PendingBadges,
+    FtMetadata,
+    ImportedStakers,
 }
 
 #[near]
@@ -80,59 +243,173 @@ impl Contract {
             total_nft_staked: 0,
 
             total_score: 0,
+            total_staked_score: 0,
             total_distribute: 0,
             total_donation: 0,
             ranking: TreeMap::new(StorageKey::Ranking),
             scores: LookupMap::new(StorageKey::Scores),
+
+            donation_amounts: LookupMap::new(StorageKey::DonationAmounts),
+            donor_ranking: TreeMap::new(StorageKey::DonorRanking),
+            received_donations: LookupMap::new(StorageKey::ReceivedDonations),
+            escrowed_donations: LookupMap::new(StorageKey::EscrowedDonations),
+            total_escrowed: 0,
+            donation_split: DonationSplit::default(),
+            donation_split_totals: DonationSplitTotals::default(),
+
+            prize_pools: LookupMap::new(StorageKey::PrizePools),
+            total_unpaid_prizes: 0,
+
+            pro_rata_distributions: LookupMap::new(StorageKey::ProRataDistributions),
+            pro_rata_distribution_count: 0,
+            pro_rata_last_paid: LookupMap::new(StorageKey::ProRataLastPaid),
+            pro_rata_snapshots: LookupMap::new(StorageKey::ProRataSnapshots),
+
+            reward_stream: None,
+            stream_debts: LookupMap::new(StorageKey::StreamDebts),
+            stream_pending: LookupMap::new(StorageKey::StreamPending),
+            stream_unclaimed: 0,
+
+            vesting_config: None,
+            vesting_schedules: LookupMap::new(StorageKey::VestingSchedules),
+            total_vesting: 0,
+
+            points_accruals: LookupMap::new(StorageKey::PointsAccruals),
+            points_checkpoints: LookupMap::new(StorageKey::PointsCheckpoints),
+
+            streak_tiers: vec![],
+            streaks: LookupMap::new(StorageKey::Streaks),
+
+            levels: vec![],
+
+            badge_config: None,
+            badges: LookupMap::new(StorageKey::Badges),
+            pending_badges: LookupMap::new(StorageKey::PendingBadges),
+
+            shop_items: LookupMap::new(StorageKey::ShopItems),
+            shop_item_count: 0,
+            redemptions: LookupMap::new(StorageKey::Redemptions),
+            redemption_count: 0,
+            account_redemptions: LookupMap::new(StorageKey::AccountRedemptions),
+
+            transfer_policy: TransferPolicy::Disabled,
+            ft_metadata: LazyOption::new(StorageKey::FtMetadata, Some(default_ft_metadata())),
+
+            reward_statuses: LookupMap::new(StorageKey::RewardStatuses),
+            reward_ids: Vector::new(StorageKey::RewardIds),
+            reward_id_cursor: 0,
+
+            moderators: vec![],
+            slashes: Vector::new(StorageKey::Slashes),
+            banned_accounts: LookupSet::new(StorageKey::BannedAccounts),
+            banned_tokens: LookupSet::new(StorageKey::BannedTokens),
+
+            stakers: TreeMap::new(StorageKey::Stakers),
+            staker_ids: LookupMap::new(StorageKey::StakerIds),
+            staker_nonce: 0,
+            scored_tokens: Vector::new(StorageKey::ScoredTokens),
+            scored_token_set: LookupSet::new(StorageKey::ScoredTokenSet),
+            scored_token_count: 0,
+            ranking_backfill_cursor: None,
+            ranking_backfill_done: true,
+
+            import_sealed: false,
+            imported_stakers: LookupMap::new(StorageKey::ImportedStakers),
+
+            referral_config: ReferralConfig::default(),
+            referrals: LookupMap::new(StorageKey::Referrals),
+            referrer_stats: LookupMap::new(StorageKey::ReferrerStats),
         }
     }
 
-    pub fn send_rewards(&mut self, account_id: AccountId, amount: U128) -> Promise {
+    /// Sends rewards to an account, doubled for stakers. The `reward_id` needs to be unique, so
+    /// that retries of the operator can't pay twice. The optional `reason` code and `memo` are
+    /// forwarded into the transfer memo and events.
+    pub fn send_rewards(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        reward_id: String,
+        reason: Option<String>,
+        memo: Option<String>,
+    ) -> PromiseOrValue<()> {
         self.require_operator();
-
-        let (some_primary_nft_token_id, amount) =
-            if let Some(primary_nft) = self.account_to_token_id.get(&account_id) {
-                (Some(primary_nft.clone()), U128(amount.0 * 2))
-            } else {
-                (None, amount)
-            };
+        self.internal_register_reward_id(reward_id.clone(), REWARD_ID_RETENTION);
+        let reason = ScoreReason::new(reason, memo);
+
+        let (some_primary_nft_token_id, amount) = match self.account_to_token_id.get(&account_id) {
+            // banned stakers are paid like accounts without staked NFT
+            Some(primary_nft) if !self.internal_is_banned(primary_nft) => (
+                Some(primary_nft.clone()),
+                U128(self.internal_apply_level_perk(primary_nft, amount.0 * 2)),
+            ),
+            _ => (None, amount),
+        };
 
         RewarderEvent::RewardSent {
             account_id: account_id.clone(),
             token_id: some_primary_nft_token_id.clone(),
             amount,
+            reason: reason.code.clone(),
+            memo: reason.memo.clone(),
         }
         .emit();
 
+        if let Some(config) = self.vesting_config.clone() {
+            self.internal_create_vesting_schedule(account_id, amount.0, &config);
+            self.internal_set_reward_status(&reward_id, RewardStatus::Sent);
+            self.internal_record_reward(some_primary_nft_token_id, amount.0, &reason);
+
+            return PromiseOrValue::Value(());
+        }
+
         ext_ft_core::ext(self.reward_token.clone())
             .with_static_gas(GAS_FOR_FT_TRANSFER)
             .with_attached_deposit(NearToken::from_yoctonear(1))
-            .ft_transfer(account_id.clone(), amount, None)
+            .ft_transfer(account_id.clone(), amount, reason.to_memo())
             .then(
                 Self::ext(env::current_account_id())
                     .with_unused_gas_weight(1)
-                    .on_reward_sent(some_primary_nft_token_id, amount),
+                    .on_reward_sent(reward_id, some_primary_nft_token_id, amount, reason),
             )
+            .into()
     }
 
     #[private]
-    pub fn on_reward_sent(&mut self, primary_nft: Option<TokenId>, amount: U128) {
-        if let Some(primary_nft) = primary_nft {
-            self.internal_record_score(primary_nft, amount.0);
-        }
-
-        self.total_distribute =
-            (U256::from(self.total_distribute) + U256::from(amount.0)).as_u128();
+    pub fn on_reward_sent(
+        &mut self,
+        reward_id: String,
+        primary_nft: Option<TokenId>,
+        amount: U128,
+        reason: ScoreReason,
+    ) {
+        let status = if let PromiseResult::Successful(_) = env::promise_result(0) {
+            self.internal_record_reward(primary_nft, amount.0, &reason);
+            RewardStatus::Sent
+        } else {
+            RewardStatus::Failed
+        };
+        self.internal_set_reward_status(&reward_id, status);
     }
 
-    pub fn on_track_score(&mut self, primary_nft: TokenId, amount: U128) {
+    pub fn on_track_score(
+        &mut self,
+        primary_nft: TokenId,
+        amount: U128,
+        reason: Option<String>,
+        memo: Option<String>,
+    ) {
         require!(
             self.whitelisted_record_score_ids
                 .contains(&env::predecessor_account_id()),
             "Only whitelisted contracts can call this function"
         );
 
-        self.internal_record_score(primary_nft, amount.0);
+        self.internal_record_score_with_reason(
+            primary_nft,
+            amount.0,
+            &ScoreReason::new(reason, memo),
+        );
     }
 }
 
@@ -140,45 +417,150 @@ impl Contract {
     fn require_operator(&self) {
         require!(
             env::predecessor_account_id() == self.operator,
-            "Only operator can send rewards"
+            "Only operator can call this method"
         );
     }
 
-    fn internal_record_score(&mut self, primary_nft: TokenId, amount: u128) -> u128 {
-        let score = *self.scores.get(&primary_nft).unwrap_or(&0);
-        let new_score = (U256::from(score) + U256::from(amount)).as_u128();
-        self.scores.set(primary_nft.clone(), Some(new_score));
+    fn internal_record_reward(
+        &mut self,
+        primary_nft: Option<TokenId>,
+        amount: u128,
+        reason: &ScoreReason,
+    ) {
+        // the token might have been unstaked while the transfer was in flight
+        let primary_nft =
+            primary_nft.filter(|token_id| self.token_id_to_account.contains_key(token_id));
+        if let Some(primary_nft) = primary_nft {
+            self.internal_record_score_with_reason(primary_nft, amount, reason);
+        }
 
-        // remove from old ranking
-        let mut ranking = self.ranking.get(&score).unwrap_or(&Vec::new()).clone();
-        ranking.retain(|x| x != &primary_nft);
+        self.total_distribute = (U256::from(self.total_distribute) + U256::from(amount)).as_u128();
+    }
 
-        if ranking.is_empty() {
-            self.ranking.remove(&score);
-        } else {
-            self.ranking.insert(score, ranking);
+    fn internal_record_score(&mut self, primary_nft: TokenId, amount: u128) -> u128 {
+        self.internal_record_score_with_reason(primary_nft, amount, &ScoreReason::default())
+    }
+
+    fn internal_record_score_with_reason(
+        &mut self,
+        primary_nft: TokenId,
+        amount: u128,
+        reason: &ScoreReason,
+    ) -> u128 {
+        if self.internal_is_banned(&primary_nft) {
+            return 0;
         }
 
-        let mut ranking = self.ranking.get(&new_score).unwrap_or(&Vec::new()).clone();
-        ranking.push(primary_nft.clone());
-        self.ranking.insert(new_score, ranking);
+        self.internal_settle_points(&primary_nft);
+        let amount = self.internal_apply_streak(&primary_nft, amount);
+
+        self.internal_add_score(primary_nft.clone(), amount, reason);
+        self.internal_credit_referrer(&primary_nft, amount);
+
+        amount
+    }
+
+    fn internal_add_score(&mut self, primary_nft: TokenId, amount: u128, reason: &ScoreReason) {
+        self.internal_settle_stream(&primary_nft);
+
+        let score = *self.scores.get(&primary_nft).unwrap_or(&0);
+        let new_score = (U256::from(score) + U256::from(amount)).as_u128();
+        self.internal_set_score(&primary_nft, score, new_score);
 
         self.total_score += amount;
+        self.total_staked_score += amount;
+        self.internal_reset_stream_debt(&primary_nft);
+
         let owner_id = self.token_id_to_account.get(&primary_nft).unwrap();
+        let memo = reason.to_memo();
         FtMint {
             owner_id,
             amount: amount.into(),
-            memo: None,
+            memo: memo.as_deref(),
         }
         .emit();
 
         RewarderEvent::ScoreRecorded {
             token_id: primary_nft,
             score: U128(new_score),
+            reason: reason.code.clone(),
+            memo: reason.memo.clone(),
         }
         .emit();
+    }
 
-        amount
+    /// Burns score of a token. Only score of staked tokens is backed by Shit Stars.
+    pub(crate) fn internal_remove_score(
+        &mut self,
+        token_id: TokenId,
+        amount: u128,
+        reason: &ScoreReason,
+    ) -> u128 {
+        self.internal_settle_stream(&token_id);
+
+        let score = *self.scores.get(&token_id).unwrap_or(&0);
+        require!(score >= amount, "Not enough score");
+        let new_score = score - amount;
+        self.internal_set_score(&token_id, score, new_score);
+
+        self.total_score -= amount;
+        if let Some(owner_id) = self.token_id_to_account.get(&token_id) {
+            self.total_staked_score -= amount;
+
+            let memo = reason.to_memo();
+            FtBurn {
+                owner_id,
+                amount: amount.into(),
+                memo: memo.as_deref(),
+            }
+            .emit();
+        }
+        self.internal_reset_stream_debt(&token_id);
+
+        RewarderEvent::ScoreRecorded {
+            token_id,
+            score: U128(new_score),
+            reason: reason.code.clone(),
+            memo: reason.memo.clone(),
+        }
+        .emit();
+
+        new_score
+    }
+
+    /// Sets the score of a token and updates the ranking. Every score change goes through here,
+    /// so that raised scores emit level ups and earn badges whatever raised them.
+    fn internal_set_score(&mut self, token_id: &TokenId, score: u128, new_score: u128) {
+        self.internal_backfill_scored_token(token_id);
+        self.internal_snapshot_pro_rata(token_id);
+        self.scores.set(token_id.clone(), Some(new_score));
+        if score == 0 && new_score > 0 {
+            self.scored_token_count += 1;
+            self.internal_index_scored_token(token_id);
+        } else if score > 0 && new_score == 0 {
+            self.scored_token_count -= 1;
+        }
+
+        // remove from old ranking
+        let mut ranking = self.ranking.get(&score).unwrap_or(&Vec::new()).clone();
+        ranking.retain(|x| x != token_id);
+
+        if ranking.is_empty() {
+            self.ranking.remove(&score);
+        } else {
+            self.ranking.insert(score, ranking);
+        }
+
+        if new_score > 0 {
+            let mut ranking = self.ranking.get(&new_score).unwrap_or(&Vec::new()).clone();
+            ranking.push(token_id.clone());
+            self.ranking.insert(new_score, ranking);
+        }
+
+        if new_score > score {
+            self.internal_emit_level_up(token_id, score);
+            self.internal_check_score_badges(token_id);
+        }
     }
 }
 
@@ -194,7 +576,7 @@ mod tests {
     use super::*;
 
     #[test]
-    #[should_panic(expected = "Only operator can send rewards")]
+    #[should_panic(expected = "Only operator can call this method")]
     fn test_panic_non_operator_cannot_send_rewards() {
         let reward_token: AccountId = "reward_token".parse().unwrap();
         let nft: AccountId = "nft".parse().unwrap();
@@ -211,7 +593,13 @@ mod tests {
             .build();
 
         testing_env!(context.clone());
-        contract.send_rewards(alice_id.clone(), U128(amount));
+        contract.send_rewards(
+            alice_id.clone(),
+            U128(amount),
+            "reward-1".to_string(),
+            None,
+            None,
+        );
         assert_eq!(contract.total_distribute, 0);
     }
 
@@ -238,7 +626,13 @@ mod tests {
             .attached_deposit(NearToken::from_yoctonear(1))
             .build();
         testing_env!(context.clone());
-        contract.send_rewards(alice_id.clone(), U128(amount));
+        contract.send_rewards(
+            alice_id.clone(),
+            U128(amount),
+            "reward-1".to_string(),
+            None,
+            None,
+        );
     }
 
     #[test]
@@ -410,14 +804,20 @@ mod tests {
             .attached_deposit(NearToken::from_yoctonear(1))
             .build();
         testing_env!(context.clone());
-        contract.send_rewards(alice_id.clone(), U128(amount));
+        contract.send_rewards(
+            alice_id.clone(),
+            U128(amount),
+            "reward-1".to_string(),
+            Some("campaign".to_string()),
+            Some("week 1".to_string()),
+        );
 
         let logs = get_logs();
         assert!(logs.len() == 1);
         assert_eq!(
             logs[0],
             format!(
-                r#"EVENT_JSON:{{"standard":"shitzurewarder","version":"1.0.0","event":"reward_sent","data":{{"account_id":"{}","amount":"{}","token_id":null}}}}"#,
+                r#"EVENT_JSON:{{"standard":"shitzurewarder","version":"1.1.0","event":"reward_sent","data":{{"account_id":"{}","amount":"{}","token_id":null,"reason":"campaign","memo":"week 1"}}}}"#,
                 alice_id, amount
             )
         );
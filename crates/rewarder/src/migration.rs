@@ -0,0 +1,153 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{
+    AccountId, env, near,
+    store::{LookupMap, TreeMap},
+};
+
+use crate::{Contract, ContractExt};
+
+/// State layout of the currently deployed contract. Needs to be updated together with the
+/// migration, whenever a release changes `Contract`.
+#[near(serializers = [borsh])]
+pub struct OldContract {
+    owner: AccountId,
+    operator: AccountId,
+    whitelisted_record_score_ids: Vec<AccountId>,
+
+    reward_token: AccountId,
+    nft: AccountId,
+
+    account_to_token_id: LookupMap<AccountId, TokenId>,
+    token_id_to_account: LookupMap<TokenId, AccountId>,
+    total_nft_staked: u128,
+
+    total_score: u128,
+    total_distribute: u128,
+    total_donation: u128,
+    scores: LookupMap<TokenId, u128>,
+    ranking: TreeMap<u128, Vec<TokenId>>,
+}
+
+#[near]
+impl Contract {
+    /// Migrates the state of the deployed contract, called by `upgrade` right after deploying
+    /// the new code.
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let old: OldContract = env::state_read().expect("Old state doesn't exist");
+
+        Self {
+            account_to_token_id: old.account_to_token_id,
+            token_id_to_account: old.token_id_to_account,
+            total_nft_staked: old.total_nft_staked,
+
+            total_score: old.total_score,
+            total_distribute: old.total_distribute,
+            total_donation: old.total_donation,
+            scores: old.scores,
+            ranking: old.ranking,
+
+            // the existing ranking is indexed with `backfill_scored_tokens`
+            ranking_backfill_done: false,
+            // the deployed contract already has its scores, so they can't be imported anymore
+            import_sealed: true,
+
+            ..Self::new(
+                old.owner,
+                old.operator,
+                old.whitelisted_record_score_ids,
+                old.reward_token,
+                old.nft,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::fungible_token::metadata::FungibleTokenMetadataProvider;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+    use crate::StorageKey;
+
+    #[test]
+    fn deploy_old_contract() {
+        let mut old = OldContract {
+            owner: "dao".parse().unwrap(),
+            operator: "operator".parse().unwrap(),
+            whitelisted_record_score_ids: vec![],
+            reward_token: "reward_token".parse().unwrap(),
+            nft: "nft".parse().unwrap(),
+            account_to_token_id: LookupMap::new(StorageKey::PrimaryNFT),
+            token_id_to_account: LookupMap::new(StorageKey::OwnerNFT),
+            total_nft_staked: 1,
+            total_score: 500,
+            total_distribute: 250,
+            total_donation: 0,
+            scores: LookupMap::new(StorageKey::Scores),
+            ranking: TreeMap::new(StorageKey::Ranking),
+        };
+        old.account_to_token_id.insert(accounts(1), "1".into());
+        old.token_id_to_account.insert("1".into(), accounts(1));
+        old.scores.insert("1".into(), 300);
+        old.scores.insert("2".into(), 200);
+        old.ranking.insert(300, vec!["1".into()]);
+        old.ranking.insert(200, vec!["2".into()]);
+        env::state_write(&old);
+        // flushes the collections
+        drop(old);
+    }
+
+    #[test]
+    fn test_migrate() {
+        let context = VMContextBuilder::new().build();
+        testing_env!(context);
+        deploy_old_contract();
+
+        let mut contract = Contract::migrate();
+        assert!(!contract.backfill_scored_tokens(Some(1)));
+        assert_eq!(contract.get_stats().scored_tokens, 1);
+        assert!(contract.backfill_scored_tokens(None));
+
+        assert_eq!(contract.total_score, 500);
+        assert_eq!(contract.total_staked_score, 300);
+        assert_eq!(contract.total_distribute, 250);
+        assert_eq!(contract.staker_of("1".into()), Some(accounts(1)));
+        assert_eq!(contract.score_of("2".into()).0, 200);
+        assert_eq!(contract.get_leaderboard(None).len(), 2);
+        assert_eq!(
+            contract.get_stakers(None, None),
+            vec![(0, accounts(1), "1".to_string())]
+        );
+        assert_eq!(contract.get_scored_tokens(None, None).len(), 2);
+        assert_eq!(contract.get_stats().scored_tokens, 2);
+        assert_eq!(contract.ft_metadata().symbol, "SHITSTARS");
+        assert!(contract.is_import_sealed());
+    }
+
+    #[test]
+    fn test_unstake_during_backfill() {
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.build());
+        deploy_old_contract();
+
+        let mut contract = Contract::migrate();
+        testing_env!(
+            context
+                .current_account_id(accounts(0))
+                .predecessor_account_id(accounts(0))
+                .build()
+        );
+        contract.on_unstake(accounts(1), "1".into());
+        assert_eq!(contract.get_stats().scored_tokens, 1);
+
+        assert!(contract.backfill_scored_tokens(None));
+        assert_eq!(contract.total_staked_score, 0);
+        assert_eq!(contract.get_stats().scored_tokens, 2);
+    }
+}
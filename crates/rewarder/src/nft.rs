@@ -45,12 +45,21 @@ impl Contract {
 
     #[private]
     pub fn on_unstake(&mut self, account_id: AccountId, token_id: TokenId) {
+        self.internal_backfill_scored_token(&token_id);
+        self.internal_settle_points(&token_id);
+        self.internal_snapshot_pro_rata(&token_id);
+        self.points_checkpoints.remove(&token_id);
+        self.internal_settle_stream(&token_id);
+
         self.account_to_token_id.remove(&account_id);
         self.token_id_to_account.remove(&token_id);
+        self.internal_unindex_staker(&account_id);
 
         self.total_nft_staked -= 1;
 
         let score = *self.scores.get(&token_id).unwrap_or(&0);
+        self.total_staked_score -= score;
+        self.internal_reset_stream_debt(&token_id);
         if score > 0 {
             FtBurn {
                 owner_id: &account_id,
@@ -65,19 +74,29 @@ impl Contract {
             token_id: token_id.clone(),
         }
         .emit();
+
+        self.internal_claim_staking_rewards(account_id);
     }
 }
 
 impl Contract {
     pub fn internal_on_stake(&mut self, account_id: AccountId, token_id: TokenId) {
+        self.internal_backfill_scored_token(&token_id);
+        self.internal_settle_stream(&token_id);
+        self.internal_snapshot_pro_rata(&token_id);
+
         self.account_to_token_id
             .set(account_id.clone(), Some(token_id.clone()));
 
         self.token_id_to_account
             .set(token_id.clone(), Some(account_id.clone()));
+        self.internal_index_staker(&account_id);
         self.total_nft_staked += 1;
+        self.internal_checkpoint_points(&token_id);
 
         let score = *self.scores.get(&token_id).unwrap_or(&0);
+        self.total_staked_score += score;
+        self.internal_reset_stream_debt(&token_id);
         if score > 0 {
             FtMint {
                 owner_id: &account_id,
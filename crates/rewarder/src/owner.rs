@@ -1,13 +1,30 @@
-use near_sdk::{env, near, require, AccountId, Promise};
+use near_contract_standards::fungible_token::{
+    core::ext_ft_core,
+    metadata::{FT_METADATA_SPEC, FungibleTokenMetadata},
+};
+use near_sdk::{
+    AccountId, GasWeight, NearToken, Promise, PromiseResult, env, json_types::U128, near, require,
+};
 
-use crate::{Contract, ContractExt};
+use crate::{
+    BPS_DENOMINATOR, Contract, ContractExt, GAS_FOR_FT_BALANCE_OF, GAS_FOR_FT_TRANSFER,
+    GAS_FOR_MIGRATE,
+    badge::BadgeConfig,
+    donation::DonationSplit,
+    event::RewarderEvent,
+    ft::TransferPolicy,
+    level::Level,
+    referral::ReferralConfig,
+    shop::{ItemPayout, RedemptionStatus, ShopItem},
+    streak::StreakTier,
+    stream::{RewardStream, StreamWeighting},
+    vesting::VestingConfig,
+};
 
 #[near]
 impl Contract {
-    pub fn migrate(&mut self) {
-        // empty for now
-    }
-
+    /// Deploys new code and migrates the state in the same batch, so that a failing migration
+    /// reverts the deployment.
     pub fn upgrade(&self) -> Promise {
         self.require_owner();
 
@@ -15,6 +32,13 @@ impl Contract {
 
         Promise::new(env::current_account_id())
             .deploy_contract(code)
+            .function_call_weight(
+                "migrate".to_string(),
+                vec![],
+                NearToken::from_yoctonear(0),
+                GAS_FOR_MIGRATE,
+                GasWeight(1),
+            )
             .as_return()
     }
 
@@ -30,13 +54,458 @@ impl Contract {
         self.whitelisted_record_score_ids
             .retain(|id| id != &account_id);
     }
+
+    pub fn add_moderator(&mut self, account_id: AccountId) {
+        self.require_owner();
+
+        if !self.moderators.contains(&account_id) {
+            self.moderators.push(account_id);
+        }
+    }
+
+    pub fn remove_moderator(&mut self, account_id: AccountId) {
+        self.require_owner();
+
+        self.moderators.retain(|id| id != &account_id);
+    }
+
+    pub fn set_referral_config(&mut self, config: ReferralConfig) {
+        self.require_owner();
+
+        require!(
+            u128::from(config.bps) <= BPS_DENOMINATOR,
+            "Referral share cannot exceed 100%"
+        );
+
+        self.referral_config = config;
+    }
+
+    pub fn set_donation_split(&mut self, split: DonationSplit) {
+        self.require_owner();
+
+        require!(
+            u128::from(split.treasury_bps) + u128::from(split.burn_bps) <= BPS_DENOMINATOR,
+            "Donation split cannot exceed 100%"
+        );
+        require!(
+            split.treasury_bps == 0 || split.treasury.is_some(),
+            "Treasury account is required"
+        );
+        require!(
+            split.burn_bps == 0 || split.burn_account.is_some(),
+            "Burn account is required"
+        );
+
+        self.donation_split = split;
+    }
+
+    pub fn set_prize_weights(&mut self, season: u64, weights: Vec<u16>) {
+        self.require_owner();
+
+        require!(
+            weights.iter().any(|weight| *weight > 0),
+            "At least one rank needs a payout weight"
+        );
+
+        let mut pool = self.prize_pools.get(&season).cloned().unwrap_or_default();
+        require!(pool.winners.is_none(), "Season is already closed");
+        pool.weights = weights;
+        self.prize_pools.insert(season, pool);
+    }
+
+    /// Ends a season and freezes the current top tokens as its winners, so that prizes can be
+    /// distributed.
+    pub fn close_season(&mut self, season: u64) {
+        self.require_owner();
+
+        let mut pool = self
+            .prize_pools
+            .get(&season)
+            .cloned()
+            .expect("Prize pool not found");
+        require!(pool.winners.is_none(), "Season is already closed");
+        require!(!pool.weights.is_empty(), "Prize weights not set");
+
+        let winners = self.internal_top_tokens(pool.weights.len());
+        RewarderEvent::SeasonClosed {
+            season,
+            winners: winners.clone(),
+        }
+        .emit();
+
+        // without winners, there is nothing to distribute
+        let no_winners = winners.is_empty();
+        pool.winners = Some(winners);
+        if no_winners {
+            self.internal_release_prize_remainder(&mut pool);
+        }
+        self.prize_pools.insert(season, pool);
+    }
+
+    pub fn configure_reward_stream(&mut self, rate_per_second: U128, weighting: StreamWeighting) {
+        self.require_owner();
+        // shares are based on the total staked score
+        require!(
+            self.ranking_backfill_done,
+            "Scored tokens are not backfilled yet"
+        );
+
+        self.internal_update_stream();
+        match self.reward_stream.as_mut() {
+            Some(stream) => {
+                require!(
+                    stream.weighting == weighting || stream.reward_per_share.0 == 0,
+                    "Weighting cannot change once rewards have been streamed"
+                );
+                stream.rate_per_second = rate_per_second;
+                stream.weighting = weighting;
+            }
+            None => {
+                self.reward_stream = Some(RewardStream {
+                    rate_per_second,
+                    weighting,
+                    remaining: U128(0),
+                    reward_per_share: U128(0),
+                    last_update: env::block_timestamp(),
+                });
+            }
+        }
+
+        RewarderEvent::RewardStreamConfigured {
+            rate_per_second,
+            weighting,
+        }
+        .emit();
+    }
+
+    pub fn set_vesting_config(&mut self, config: Option<VestingConfig>) {
+        self.require_owner();
+
+        if let Some(config) = &config {
+            require!(
+                config.cliff_seconds <= config.duration_seconds,
+                "Cliff cannot be longer than the vesting duration"
+            );
+        }
+
+        self.vesting_config = config;
+    }
+
+    /// Sets the staking points every staked NFT of a collection accrues per day. Points accrued
+    /// so far keep the previous rate, the new one only applies from now on.
+    pub fn set_points_per_day(&mut self, collection: AccountId, points_per_day: U128) {
+        self.require_owner();
+        // only NFTs of this collection can be staked
+        require!(collection == self.nft, "Collection can't be staked");
+
+        let now = env::block_timestamp();
+        let mut accrual = self
+            .points_accruals
+            .get(&collection)
+            .cloned()
+            .unwrap_or_default();
+        accrual.points_per_token = U128(accrual.points_per_token(now));
+        accrual.updated_at = now;
+        accrual.points_per_day = points_per_day;
+        self.points_accruals.insert(collection, accrual);
+    }
+
+    /// Sets the streak tiers, ordered by ascending `min_days`. The highest reached tier
+    /// multiplies every score a token receives.
+    pub fn set_streak_tiers(&mut self, tiers: Vec<StreakTier>) {
+        self.require_owner();
+
+        require!(
+            tiers
+                .windows(2)
+                .all(|pair| pair[0].min_days < pair[1].min_days),
+            "Streak tiers must be ordered by ascending min_days"
+        );
+
+        self.streak_tiers = tiers;
+    }
+
+    /// Sets the score levels, ordered by ascending threshold.
+    pub fn set_levels(&mut self, levels: Vec<Level>) {
+        self.require_owner();
+
+        require!(
+            levels
+                .windows(2)
+                .all(|pair| pair[0].threshold.0 < pair[1].threshold.0),
+            "Levels must be ordered by ascending threshold"
+        );
+
+        self.levels = levels;
+    }
+
+    /// Sets the contract that mints achievement badges, which is expected to make them
+    /// soulbound. `None` stops awarding badges.
+    pub fn set_badge_config(&mut self, config: Option<BadgeConfig>) {
+        self.require_owner();
+
+        self.badge_config = config;
+    }
+
+    /// Adds an item to the redemption shop and returns its id.
+    pub fn add_shop_item(&mut self, item: ShopItem) -> u64 {
+        self.require_owner();
+        item.assert_valid();
+
+        let item_id = self.shop_item_count;
+        self.shop_item_count += 1;
+        self.shop_items.insert(item_id, item);
+
+        item_id
+    }
+
+    pub fn update_shop_item(&mut self, item_id: u64, item: ShopItem) {
+        self.require_owner();
+
+        require!(self.shop_items.contains_key(&item_id), "Item not found");
+        item.assert_valid();
+        self.shop_items.insert(item_id, item);
+    }
+
+    pub fn remove_shop_item(&mut self, item_id: u64) {
+        self.require_owner();
+
+        self.shop_items.remove(&item_id).expect("Item not found");
+    }
+
+    /// Marks a redemption of an off-chain item as fulfilled.
+    pub fn fulfill_redemption(&mut self, redemption_id: u64) {
+        self.require_owner();
+
+        let redemption = self
+            .redemptions
+            .get_mut(&redemption_id)
+            .expect("Redemption not found");
+        require!(
+            redemption.payout == ItemPayout::OffChain,
+            "Only off-chain redemptions can be fulfilled manually"
+        );
+        require!(
+            redemption.status == RedemptionStatus::Pending,
+            "Redemption already fulfilled"
+        );
+        redemption.status = RedemptionStatus::Fulfilled;
+
+        RewarderEvent::RedemptionFulfilled { redemption_id }.emit();
+    }
+
+    pub fn set_transfer_policy(&mut self, policy: TransferPolicy) {
+        self.require_owner();
+
+        if let TransferPolicy::Fee { fee_bps } = policy {
+            require!(
+                u128::from(fee_bps) <= BPS_DENOMINATOR,
+                "Fee cannot exceed 100%"
+            );
+        }
+
+        self.transfer_policy = policy;
+    }
+
+    /// Replaces the Shit Stars metadata. `reference` can point to an off-chain JSON file, whose
+    /// sha256 hash is set as `reference_hash`.
+    pub fn set_ft_metadata(&mut self, metadata: FungibleTokenMetadata) {
+        self.require_owner();
+
+        require!(metadata.spec == FT_METADATA_SPEC, "Invalid metadata spec");
+        metadata.assert_valid();
+
+        RewarderEvent::FtMetadataUpdated {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            decimals: metadata.decimals,
+            reference: metadata.reference.clone(),
+            reference_hash: metadata.reference_hash.clone(),
+        }
+        .emit();
+
+        self.ft_metadata.set(Some(metadata));
+    }
+
+    /// Withdraws tokens held by the rewarder. Reward tokens can only be withdrawn as far as they
+    /// don't back escrowed donations, vesting schedules, prizes or staking rewards.
+    pub fn withdraw_tokens(
+        &mut self,
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    ) -> Promise {
+        self.require_owner();
+
+        if token == self.reward_token {
+            return ext_ft_core::ext(token)
+                .with_static_gas(GAS_FOR_FT_BALANCE_OF)
+                .ft_balance_of(env::current_account_id())
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_unused_gas_weight(1)
+                        .on_reward_token_balance(amount, receiver),
+                );
+        }
+
+        self.internal_withdraw_tokens(token, amount, receiver)
+    }
+
+    #[private]
+    pub fn on_reward_token_balance(
+        &mut self,
+        amount: U128,
+        receiver: AccountId,
+        #[callback_unwrap] balance: U128,
+    ) -> Promise {
+        let unallocated = balance
+            .0
+            .saturating_sub(self.internal_reward_token_liabilities());
+        require!(
+            amount.0 <= unallocated,
+            "Withdrawal exceeds the unallocated balance"
+        );
+
+        self.internal_withdraw_tokens(self.reward_token.clone(), amount, receiver)
+    }
+
+    #[private]
+    pub fn on_tokens_withdrawn(
+        &mut self,
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    ) -> bool {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                RewarderEvent::TokensWithdrawn {
+                    token,
+                    amount,
+                    receiver,
+                }
+                .emit();
+                true
+            }
+            PromiseResult::Failed => {
+                RewarderEvent::WithdrawFailed {
+                    token,
+                    amount,
+                    receiver,
+                }
+                .emit();
+                false
+            }
+        }
+    }
 }
 
 impl Contract {
-    fn require_owner(&self) {
+    fn internal_withdraw_tokens(
+        &self,
+        token: AccountId,
+        amount: U128,
+        receiver: AccountId,
+    ) -> Promise {
+        ext_ft_core::ext(token.clone())
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .ft_transfer(
+                receiver.clone(),
+                amount,
+                Some("Rewarder withdrawal".to_string()),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_unused_gas_weight(1)
+                    .on_tokens_withdrawn(token, amount, receiver),
+            )
+    }
+
+    /// Reward tokens held by the rewarder on behalf of others.
+    pub(crate) fn internal_reward_token_liabilities(&self) -> u128 {
+        let stream_remaining = self
+            .reward_stream
+            .as_ref()
+            .map_or(0, |stream| stream.remaining.0);
+        let pro_rata_remaining = self
+            .pro_rata_distribution_count
+            .checked_sub(1)
+            .and_then(|id| self.pro_rata_distributions.get(&id))
+            .filter(|distribution| !distribution.finished)
+            .map_or(0, |distribution| {
+                distribution.amount.0 - distribution.distributed.0 - distribution.failed.0
+            });
+
+        self.total_escrowed
+            + self.total_vesting
+            + self.total_unpaid_prizes
+            + stream_remaining
+            + self.stream_unclaimed
+            + pro_rata_remaining
+    }
+
+    pub(crate) fn require_owner(&self) {
         require!(
             env::predecessor_account_id() == self.owner,
             "Only owner can call this function"
         );
     }
+
+    /// Moderators can penalize cheaters. The owner is always a moderator.
+    pub(crate) fn require_moderator(&self) {
+        let predecessor = env::predecessor_account_id();
+        require!(
+            predecessor == self.owner || self.moderators.contains(&predecessor),
+            "Only moderators can call this function"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::fungible_token::receiver::FungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    fn setup_contract() -> Contract {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(accounts(1), U128(100), r#"{"escrow":true}"#.to_string());
+        contract.ft_on_transfer(
+            "dao".parse().unwrap(),
+            U128(300),
+            r#"{"prize_pool":1}"#.to_string(),
+        );
+
+        contract
+    }
+
+    #[test]
+    fn test_withdraw_unallocated_reward_tokens() {
+        let mut contract = setup_contract();
+        assert_eq!(contract.internal_reward_token_liabilities(), 400);
+
+        contract.on_reward_token_balance(U128(600), accounts(2), U128(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Withdrawal exceeds the unallocated balance")]
+    fn test_panic_withdraw_allocated_reward_tokens() {
+        let mut contract = setup_contract();
+
+        contract.on_reward_token_balance(U128(601), accounts(2), U128(1_000));
+    }
 }
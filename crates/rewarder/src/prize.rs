@@ -0,0 +1,460 @@
+use near_contract_standards::{fungible_token::core::ext_ft_core, non_fungible_token::TokenId};
+use near_sdk::{AccountId, NearToken, PromiseResult, env, json_types::U128, near, require};
+use primitive_types::U256;
+
+use crate::{
+    Contract, ContractExt, GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER, event::RewarderEvent,
+};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayoutStatus {
+    Pending,
+    Paid,
+    Failed,
+    /// The token of this rank was not staked or is banned, so its share is unallocated
+    Skipped,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct PrizePayout {
+    pub rank: u32,
+    pub token_id: TokenId,
+    pub account_id: Option<AccountId>,
+    pub amount: U128,
+    pub status: PayoutStatus,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct PrizePool {
+    pub amount: U128,
+    /// Payout weight of each rank, starting with the first place
+    pub weights: Vec<u16>,
+    /// Tokens of the top ranks, frozen when the season is closed
+    pub winners: Option<Vec<TokenId>>,
+    pub payouts: Vec<PrizePayout>,
+    /// Shares of skipped ranks and the rounding remainder, which the owner can withdraw
+    pub unallocated: U128,
+}
+
+impl PrizePool {
+    /// Share of a rank among the ranks that have a winner.
+    fn share_of(&self, rank: usize) -> u128 {
+        let ranks = self.winners.as_ref().map_or(0, Vec::len);
+        let total_weight: u128 = self
+            .weights
+            .iter()
+            .take(ranks)
+            .map(|w| u128::from(*w))
+            .sum();
+        (U256::from(self.amount.0) * U256::from(self.weights[rank]) / U256::from(total_weight))
+            .as_u128()
+    }
+}
+
+#[near]
+impl Contract {
+    /// Pays the next `limit` ranks of a closed season's prize pool to the current stakers of
+    /// the winning tokens. Returns the number of processed ranks.
+    pub fn distribute_prizes(&mut self, season: u64, limit: Option<u32>) -> u32 {
+        let mut pool = self
+            .prize_pools
+            .get(&season)
+            .cloned()
+            .expect("Prize pool not found");
+        let winners = pool.winners.clone().expect("Season is not closed yet");
+
+        let from = pool.payouts.len();
+        let ranks = winners.len();
+        let to = ranks.min(from + limit.unwrap_or(10) as usize);
+        require!(from < to, "Prizes already distributed");
+
+        for (rank, token_id) in winners.into_iter().enumerate().take(to).skip(from) {
+            let amount = pool.share_of(rank);
+            let account_id = self.token_id_to_account.get(&token_id).cloned();
+            let status = match &account_id {
+                // tokens banned after the season was closed forfeit their prize
+                Some(account_id) if amount > 0 && !self.internal_is_banned(&token_id) => {
+                    self.internal_send_prize(season, rank as u32, account_id.clone(), amount);
+                    PayoutStatus::Pending
+                }
+                _ => {
+                    self.internal_skip_prize(season, &mut pool, rank as u32, &token_id, amount);
+                    PayoutStatus::Skipped
+                }
+            };
+
+            pool.payouts.push(PrizePayout {
+                rank: rank as u32,
+                token_id,
+                account_id,
+                amount: U128(amount),
+                status,
+            });
+        }
+        if to == ranks {
+            self.internal_release_prize_remainder(&mut pool);
+        }
+
+        self.prize_pools.insert(season, pool);
+
+        (to - from) as u32
+    }
+
+    /// Retries a failed payout, paying the current staker of the winning token.
+    pub fn retry_prize_payout(&mut self, season: u64, rank: u32) {
+        let mut pool = self
+            .prize_pools
+            .get(&season)
+            .cloned()
+            .expect("Prize pool not found");
+        let payout = pool
+            .payouts
+            .get_mut(rank as usize)
+            .expect("Payout not found");
+        require!(
+            payout.status == PayoutStatus::Failed,
+            "Only failed payouts can be retried"
+        );
+
+        let token_id = payout.token_id.clone();
+        let amount = payout.amount.0;
+        let account_id = self.token_id_to_account.get(&token_id).cloned();
+        let status = if let Some(account_id) = account_id
+            .clone()
+            .filter(|_| !self.internal_is_banned(&token_id))
+        {
+            self.internal_send_prize(season, rank, account_id, amount);
+            PayoutStatus::Pending
+        } else {
+            self.internal_skip_prize(season, &mut pool, rank, &token_id, amount);
+            PayoutStatus::Skipped
+        };
+        let payout = &mut pool.payouts[rank as usize];
+        payout.account_id = account_id;
+        payout.status = status;
+
+        self.prize_pools.insert(season, pool);
+    }
+
+    #[private]
+    pub fn on_prize_sent(&mut self, season: u64, rank: u32) {
+        let mut pool = self.prize_pools.get(&season).cloned().unwrap();
+        let payout = &mut pool.payouts[rank as usize];
+        let account_id = payout.account_id.clone().unwrap();
+
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            payout.status = PayoutStatus::Paid;
+            self.total_unpaid_prizes -= payout.amount.0;
+
+            RewarderEvent::PrizePaid {
+                season,
+                rank,
+                token_id: payout.token_id.clone(),
+                account_id,
+                amount: payout.amount,
+            }
+            .emit();
+        } else {
+            payout.status = PayoutStatus::Failed;
+
+            RewarderEvent::PrizePayoutFailed {
+                season,
+                rank,
+                token_id: payout.token_id.clone(),
+                account_id,
+                amount: payout.amount,
+            }
+            .emit();
+        }
+
+        self.prize_pools.insert(season, pool);
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_fund_prize_pool(
+        &mut self,
+        sender_id: AccountId,
+        season: u64,
+        amount: u128,
+    ) {
+        require!(sender_id == self.owner, "Only owner can fund prize pools");
+
+        let mut pool = self.prize_pools.get(&season).cloned().unwrap_or_default();
+        require!(pool.winners.is_none(), "Season is already closed");
+        pool.amount = U128(pool.amount.0 + amount);
+        self.total_unpaid_prizes += amount;
+
+        RewarderEvent::PrizePoolFunded {
+            season,
+            amount: U128(amount),
+            total: pool.amount,
+        }
+        .emit();
+
+        self.prize_pools.insert(season, pool);
+    }
+
+    /// Releases the rounding remainder of a pool, once all of its ranks are processed.
+    pub(crate) fn internal_release_prize_remainder(&mut self, pool: &mut PrizePool) {
+        let assigned: u128 = pool.payouts.iter().map(|payout| payout.amount.0).sum();
+        let remainder = pool.amount.0 - assigned;
+        self.total_unpaid_prizes -= remainder;
+        pool.unallocated = U128(pool.unallocated.0 + remainder);
+    }
+
+    fn internal_skip_prize(
+        &mut self,
+        season: u64,
+        pool: &mut PrizePool,
+        rank: u32,
+        token_id: &TokenId,
+        amount: u128,
+    ) {
+        self.total_unpaid_prizes -= amount;
+        pool.unallocated = U128(pool.unallocated.0 + amount);
+
+        RewarderEvent::PrizeSkipped {
+            season,
+            rank,
+            token_id: token_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+    }
+
+    /// Highest ranked tokens, banned ones excluded.
+    pub(crate) fn internal_top_tokens(&self, limit: usize) -> Vec<TokenId> {
+        self.ranking
+            .iter()
+            .rev()
+            .flat_map(|(_, token_ids)| token_ids.iter().cloned())
+            .filter(|token_id| !self.internal_is_banned(token_id))
+            .take(limit)
+            .collect()
+    }
+
+    fn internal_send_prize(&self, season: u64, rank: u32, account_id: AccountId, amount: u128) {
+        ext_ft_core::ext(self.reward_token.clone())
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .ft_transfer(
+                account_id,
+                U128(amount),
+                Some(format!("Season {season} prize for rank {}", rank + 1)),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_prize_sent(season, rank),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::{
+        fungible_token::receiver::FungibleTokenReceiver,
+        non_fungible_token::core::NonFungibleTokenReceiver,
+    };
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_distribute_prizes_to_top_ranks() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator,
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("2".into(), 200);
+        contract.nft_on_transfer(accounts(0), accounts(3), "3".into(), "".into());
+        contract.internal_record_score("3".into(), 100);
+        contract.on_unstake(accounts(2), "2".into());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(dao.clone(), U128(1_000), r#"{"prize_pool":1}"#.into());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_prize_weights(1, vec![5, 3, 2]);
+        contract.close_season(1);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(4))
+            .build();
+        testing_env!(context);
+        assert_eq!(contract.distribute_prizes(1, Some(2)), 2);
+        assert_eq!(contract.distribute_prizes(1, None), 1);
+
+        let payouts = contract.get_prize_pool(1).unwrap().payouts;
+        assert_eq!(
+            payouts
+                .iter()
+                .map(|payout| (
+                    payout.token_id.as_str(),
+                    payout.amount,
+                    payout.status.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("1", U128(500), PayoutStatus::Pending),
+                ("2", U128(300), PayoutStatus::Skipped),
+                ("3", U128(200), PayoutStatus::Pending),
+            ]
+        );
+        assert_eq!(contract.get_prize_pool(1).unwrap().unallocated, U128(300));
+        assert_eq!(contract.total_unpaid_prizes, 700);
+    }
+
+    #[test]
+    fn test_prize_of_token_banned_after_close_is_skipped() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator,
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("2".into(), 200);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(dao.clone(), U128(1_000), r#"{"prize_pool":1}"#.into());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_prize_weights(1, vec![1, 1]);
+        contract.close_season(1);
+        contract.ban_token("1".into(), None);
+        contract.distribute_prizes(1, None);
+
+        let pool = contract.get_prize_pool(1).unwrap();
+        assert_eq!(pool.payouts[0].status, PayoutStatus::Skipped);
+        assert_eq!(pool.payouts[1].status, PayoutStatus::Pending);
+        assert_eq!(pool.unallocated, U128(500));
+    }
+
+    #[test]
+    fn test_prize_shares_of_ranks_without_winner() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator,
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("2".into(), 200);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(dao.clone(), U128(1_000), r#"{"prize_pool":1}"#.into());
+
+        // only two of three ranks have a winner
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_prize_weights(1, vec![2, 1, 1]);
+        contract.close_season(1);
+        contract.distribute_prizes(1, None);
+
+        let pool = contract.get_prize_pool(1).unwrap();
+        assert_eq!(pool.payouts[0].amount, U128(666));
+        assert_eq!(pool.payouts[1].amount, U128(333));
+        // the rounding remainder is released
+        assert_eq!(pool.unallocated, U128(1));
+        assert_eq!(contract.total_unpaid_prizes, 999);
+    }
+
+    #[test]
+    #[should_panic(expected = "Season is not closed yet")]
+    fn test_panic_distribute_prizes_before_season_end() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token.clone(), nft);
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(dao.clone(), U128(1_000), r#"{"prize_pool":1}"#.into());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_prize_weights(1, vec![5, 3, 2]);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(4))
+            .build();
+        testing_env!(context);
+        contract.distribute_prizes(1, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can fund prize pools")]
+    fn test_panic_non_owner_funds_prize_pool() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft);
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(accounts(1), U128(1_000), r#"{"prize_pool":1}"#.into());
+    }
+}
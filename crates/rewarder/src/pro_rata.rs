@@ -0,0 +1,344 @@
+use near_contract_standards::{fungible_token::core::ext_ft_core, non_fungible_token::TokenId};
+use near_sdk::{AccountId, NearToken, PromiseResult, env, json_types::U128, near, require};
+use primitive_types::U256;
+
+use crate::{
+    Contract, ContractExt, GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER, event::RewarderEvent,
+};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct ProRataDistribution {
+    pub amount: U128,
+    /// Score of all staked tokens when the distribution started
+    pub total_score: U128,
+    /// Next scored token index to process
+    pub cursor: u32,
+    /// Number of scored tokens when the distribution started, later ones don't take part
+    pub end: u32,
+    pub distributed: U128,
+    pub failed: U128,
+    /// Rounding leftover and shares of tokens unstaked or banned in the meantime, which stay in
+    /// the rewarder once the distribution is finished
+    pub dust: U128,
+    pub finished: bool,
+}
+
+#[near]
+impl Contract {
+    /// Pays the stakers of the next batch of scored tokens their share of the active pro-rata
+    /// distribution. Returns whether the distribution is finished.
+    pub fn process_pro_rata_distribution(&mut self, limit: Option<u32>) -> bool {
+        let id = self
+            .pro_rata_distribution_count
+            .checked_sub(1)
+            .expect("No pro-rata distribution found");
+        let mut distribution = self.pro_rata_distributions.get(&id).cloned().unwrap();
+        require!(!distribution.finished, "Distribution already finished");
+
+        // every payout needs (8 + 5) Tgas
+        let to = distribution
+            .end
+            .min(distribution.cursor + limit.unwrap_or(10));
+        for index in distribution.cursor..to {
+            let token_id = self.scored_tokens[index].clone();
+            self.internal_pay_pro_rata(id, &mut distribution, token_id);
+        }
+        distribution.cursor = to;
+        let finished = to == distribution.end;
+
+        if finished {
+            distribution.finished = true;
+            distribution.dust = U128(distribution.amount.0 - distribution.distributed.0);
+
+            RewarderEvent::ProRataDistributionFinished {
+                id,
+                distributed: distribution.distributed,
+                dust: distribution.dust,
+            }
+            .emit();
+        }
+
+        self.pro_rata_distributions.insert(id, distribution);
+
+        finished
+    }
+
+    #[private]
+    pub fn on_pro_rata_sent(&mut self, id: u64, account_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            self.total_distribute =
+                (U256::from(self.total_distribute) + U256::from(amount.0)).as_u128();
+            return;
+        }
+
+        let distribution = self.pro_rata_distributions.get_mut(&id).unwrap();
+        distribution.distributed = U128(distribution.distributed.0 - amount.0);
+        distribution.failed = U128(distribution.failed.0 + amount.0);
+
+        RewarderEvent::ProRataRewardFailed {
+            id,
+            account_id,
+            amount,
+        }
+        .emit();
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_start_pro_rata_distribution(
+        &mut self,
+        sender_id: AccountId,
+        amount: u128,
+    ) {
+        require!(
+            sender_id == self.operator,
+            "Only operator can start pro-rata distributions"
+        );
+        require!(
+            self.ranking_backfill_done,
+            "Scored tokens are not backfilled yet"
+        );
+        require!(
+            self.total_staked_score > 0,
+            "No staked score to distribute to"
+        );
+        if let Some(id) = self.pro_rata_distribution_count.checked_sub(1) {
+            require!(
+                self.pro_rata_distributions.get(&id).unwrap().finished,
+                "Previous distribution not finished"
+            );
+        }
+
+        let id = self.pro_rata_distribution_count;
+        self.pro_rata_distribution_count += 1;
+        self.pro_rata_distributions.insert(
+            id,
+            ProRataDistribution {
+                amount: U128(amount),
+                total_score: U128(self.total_staked_score),
+                cursor: 0,
+                end: self.scored_tokens.len(),
+                distributed: U128(0),
+                failed: U128(0),
+                dust: U128(0),
+                finished: false,
+            },
+        );
+
+        RewarderEvent::ProRataDistributionStarted {
+            id,
+            amount: U128(amount),
+            total_score: U128(self.total_staked_score),
+        }
+        .emit();
+    }
+
+    /// The active distribution, which is not finished yet.
+    fn internal_active_pro_rata(&self) -> Option<u64> {
+        self.pro_rata_distribution_count
+            .checked_sub(1)
+            .filter(|id| !self.pro_rata_distributions.get(id).unwrap().finished)
+    }
+
+    /// Staked score of a token when the active distribution started.
+    fn internal_pro_rata_score(&self, id: u64, token_id: &TokenId) -> u128 {
+        match self.pro_rata_snapshots.get(token_id) {
+            Some((snapshot_id, score)) if *snapshot_id == id => *score,
+            // unchanged since the distribution started
+            _ if self.token_id_to_account.contains_key(token_id) => {
+                *self.scores.get(token_id).unwrap_or(&0)
+            }
+            _ => 0,
+        }
+    }
+
+    /// Remembers the staked score of a token before it changes while a distribution is active,
+    /// so that the token is paid according to its score at the start.
+    pub(crate) fn internal_snapshot_pro_rata(&mut self, token_id: &TokenId) {
+        let Some(id) = self.internal_active_pro_rata() else {
+            return;
+        };
+        if self.pro_rata_last_paid.get(token_id) == Some(&id) {
+            return;
+        }
+        if self
+            .pro_rata_snapshots
+            .get(token_id)
+            .is_some_and(|(snapshot_id, _)| *snapshot_id == id)
+        {
+            return;
+        }
+
+        let score = self.internal_pro_rata_score(id, token_id);
+        self.pro_rata_snapshots
+            .insert(token_id.clone(), (id, score));
+    }
+
+    fn internal_pay_pro_rata(
+        &mut self,
+        id: u64,
+        distribution: &mut ProRataDistribution,
+        token_id: TokenId,
+    ) {
+        let score = self.internal_pro_rata_score(id, &token_id);
+        self.pro_rata_snapshots.remove(&token_id);
+        self.pro_rata_last_paid.insert(token_id.clone(), id);
+        let Some(account_id) = self.token_id_to_account.get(&token_id).cloned() else {
+            return;
+        };
+        // the share of banned tokens stays in the rewarder as dust
+        if self.internal_is_banned(&token_id) {
+            return;
+        }
+
+        let amount = (U256::from(distribution.amount.0) * U256::from(score)
+            / U256::from(distribution.total_score.0))
+        .as_u128();
+        if amount == 0 {
+            return;
+        }
+        distribution.distributed = U128(distribution.distributed.0 + amount);
+
+        RewarderEvent::ProRataRewardSent {
+            id,
+            account_id: account_id.clone(),
+            token_id,
+            amount: U128(amount),
+        }
+        .emit();
+
+        ext_ft_core::ext(self.reward_token.clone())
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .ft_transfer(
+                account_id.clone(),
+                U128(amount),
+                Some("Pro-rata reward".to_string()),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_pro_rata_sent(id, account_id, U128(amount)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::{
+        fungible_token::receiver::FungibleTokenReceiver,
+        non_fungible_token::core::NonFungibleTokenReceiver,
+    };
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_pro_rata_distribution_in_batches() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao,
+            operator.clone(),
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 100);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("2".into(), 200);
+        contract.nft_on_transfer(accounts(0), accounts(3), "3".into(), "".into());
+        contract.internal_record_score("3".into(), 400);
+        // unstaked tokens are not part of the distribution
+        contract.nft_on_transfer(accounts(0), accounts(4), "4".into(), "".into());
+        contract.internal_record_score("4".into(), 1_000);
+        contract.on_unstake(accounts(4), "4".into());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(
+            operator,
+            U128(1_000),
+            r#"{"pro_rata_distribution":true}"#.into(),
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(5))
+            .build();
+        testing_env!(context);
+        assert!(!contract.process_pro_rata_distribution(Some(2)));
+        assert_eq!(contract.get_pro_rata_distribution(0).unwrap().cursor, 2);
+        assert!(contract.process_pro_rata_distribution(Some(2)));
+
+        let distribution = contract.get_pro_rata_distribution(0).unwrap();
+        // 1000 * 100 / 700 + 1000 * 200 / 700 + 1000 * 400 / 700 = 142 + 285 + 571
+        assert_eq!(distribution.distributed, U128(998));
+        assert_eq!(distribution.dust, U128(2));
+        assert!(distribution.finished);
+    }
+
+    #[test]
+    fn test_pro_rata_uses_scores_at_start() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao,
+            operator.clone(),
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 100);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("2".into(), 300);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(
+            operator,
+            U128(1_000),
+            r#"{"pro_rata_distribution":true}"#.into(),
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        // changes after the start don't affect the shares
+        contract.internal_record_score("1".into(), 10_000);
+        contract.nft_on_transfer(accounts(0), accounts(3), "3".into(), "".into());
+        contract.internal_record_score("3".into(), 10_000);
+
+        assert!(contract.process_pro_rata_distribution(None));
+
+        let distribution = contract.get_pro_rata_distribution(0).unwrap();
+        assert_eq!(distribution.distributed, U128(1_000));
+        assert_eq!(distribution.dust, U128(0));
+    }
+}
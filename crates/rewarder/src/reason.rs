@@ -0,0 +1,52 @@
+use near_sdk::near;
+
+/// Context of a score change, forwarded into transfer memos and events, so that every change can
+/// be reconciled with what triggered it.
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScoreReason {
+    /// Reason code, e.g. the campaign that triggered a reward
+    pub code: Option<String>,
+    pub memo: Option<String>,
+}
+
+impl ScoreReason {
+    pub fn new(code: Option<String>, memo: Option<String>) -> Self {
+        Self { code, memo }
+    }
+
+    pub fn code(code: &str) -> Self {
+        Self {
+            code: Some(code.to_string()),
+            memo: None,
+        }
+    }
+
+    /// Memo of token transfers and NEP-141 events, combining reason code and memo.
+    pub fn to_memo(&self) -> Option<String> {
+        match (&self.code, &self.memo) {
+            (Some(code), Some(memo)) => Some(format!("{code}: {memo}")),
+            (Some(code), None) => Some(code.clone()),
+            (None, Some(memo)) => Some(memo.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_memo() {
+        assert_eq!(ScoreReason::default().to_memo(), None);
+        assert_eq!(
+            ScoreReason::code("contest").to_memo(),
+            Some("contest".to_string())
+        );
+        assert_eq!(
+            ScoreReason::new(Some("contest".to_string()), Some("week 3".to_string())).to_memo(),
+            Some("contest: week 3".to_string())
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, json_types::U128, near, require};
+use primitive_types::U256;
+
+use crate::{BPS_DENOMINATOR, Contract, event::RewarderEvent, reason::ScoreReason};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Default)]
+pub struct ReferralConfig {
+    /// Share of every score a referee earns that is credited to the referrer, in basis points
+    pub bps: u16,
+    /// Maximum score a single referee can generate for their referrer
+    pub cap_per_referee: Option<U128>,
+    /// Maximum number of referees a single account can register
+    pub max_referees: Option<u32>,
+}
+
+#[near(serializers = [borsh])]
+pub struct Referral {
+    pub referrer: AccountId,
+    /// Score already credited to the referrer on behalf of this referee
+    pub credited: u128,
+}
+
+#[near(serializers = [borsh])]
+#[derive(Default)]
+pub struct ReferrerStats {
+    pub referees: u32,
+    pub earned: u128,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReferralStatsView {
+    pub referrer: Option<AccountId>,
+    pub referees: u32,
+    pub earned: U128,
+    pub generated: U128,
+}
+
+#[near(serializers = [json])]
+#[derive(Default)]
+pub struct StakeMsg {
+    pub referrer: Option<AccountId>,
+}
+
+impl Contract {
+    pub(crate) fn internal_register_referral(
+        &mut self,
+        account_id: AccountId,
+        referrer: AccountId,
+    ) {
+        require!(account_id != referrer, "Cannot refer yourself");
+        if let Some(referral) = self.referrals.get(&account_id) {
+            // restaking with the same referrer
+            require!(referral.referrer == referrer, "Referrer already set");
+            return;
+        }
+
+        let mut stats = self.referrer_stats.remove(&referrer).unwrap_or_default();
+        if let Some(max_referees) = self.referral_config.max_referees {
+            require!(
+                stats.referees < max_referees,
+                "Referrer has reached the maximum number of referees"
+            );
+        }
+        stats.referees += 1;
+        self.referrer_stats.insert(referrer.clone(), stats);
+
+        self.referrals.insert(
+            account_id.clone(),
+            Referral {
+                referrer: referrer.clone(),
+                credited: 0,
+            },
+        );
+
+        RewarderEvent::ReferralRegistered {
+            account_id,
+            referrer,
+        }
+        .emit();
+    }
+
+    pub(crate) fn internal_credit_referrer(&mut self, token_id: &TokenId, amount: u128) {
+        if self.referral_config.bps == 0 {
+            return;
+        }
+        let Some(referee) = self.token_id_to_account.get(token_id).cloned() else {
+            return;
+        };
+        let Some(referral) = self.referrals.get(&referee) else {
+            return;
+        };
+        let Some(referrer_token_id) = self.account_to_token_id.get(&referral.referrer).cloned()
+        else {
+            return;
+        };
+        if self.internal_is_banned(&referrer_token_id) {
+            return;
+        }
+
+        let mut credit = (U256::from(amount) * U256::from(self.referral_config.bps)
+            / U256::from(BPS_DENOMINATOR))
+        .as_u128();
+        if let Some(cap) = self.referral_config.cap_per_referee {
+            credit = credit.min(cap.0.saturating_sub(referral.credited));
+        }
+        if credit == 0 {
+            return;
+        }
+
+        let referrer = referral.referrer.clone();
+        if let Some(referral) = self.referrals.get_mut(&referee) {
+            referral.credited += credit;
+        }
+        let mut stats = self.referrer_stats.remove(&referrer).unwrap_or_default();
+        stats.earned += credit;
+        self.referrer_stats.insert(referrer.clone(), stats);
+
+        // credited score does not go through `internal_record_score` to avoid referral chains
+        self.internal_add_score(
+            referrer_token_id.clone(),
+            credit,
+            &ScoreReason::code("referral"),
+        );
+
+        RewarderEvent::ReferralCredited {
+            referrer,
+            referee,
+            token_id: referrer_token_id,
+            amount: U128(credit),
+        }
+        .emit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    fn setup_contract() -> Contract {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft.clone());
+        contract.referral_config = ReferralConfig {
+            bps: 1_000,
+            cap_per_referee: Some(U128(150)),
+            max_referees: Some(1),
+        };
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+
+        contract
+    }
+
+    #[test]
+    fn test_referrer_receives_share_of_score() {
+        let mut contract = setup_contract();
+        let alice = accounts(1);
+        let bob = accounts(2);
+
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+        contract.nft_on_transfer(
+            accounts(0),
+            bob.clone(),
+            "2".into(),
+            format!(r#"{{"referrer":"{}"}}"#, alice),
+        );
+
+        contract.internal_record_score("2".into(), 1_000);
+        assert_eq!(contract.score_of("2".into()), U128(1_000));
+        assert_eq!(contract.score_of("1".into()), U128(100));
+
+        // capped at 150 per referee
+        contract.internal_record_score("2".into(), 1_000);
+        assert_eq!(contract.score_of("1".into()), U128(150));
+
+        assert_eq!(
+            contract.referral_stats_of(alice),
+            ReferralStatsView {
+                referrer: None,
+                referees: 1,
+                earned: U128(150),
+                generated: U128(0),
+            }
+        );
+        assert_eq!(contract.total_score, 2_150);
+    }
+
+    #[test]
+    fn test_restake_with_same_referrer_and_invalid_msg() {
+        let mut contract = setup_contract();
+        let alice = accounts(1);
+        let bob = accounts(2);
+        let msg = format!(r#"{{"referrer":"{}"}}"#, alice);
+
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "not json".into());
+        contract.nft_on_transfer(accounts(0), bob.clone(), "2".into(), msg.clone());
+        contract.on_unstake(bob.clone(), "2".into());
+        contract.nft_on_transfer(accounts(0), bob.clone(), "2".into(), msg);
+
+        assert_eq!(contract.staker_of("1".into()), Some(alice.clone()));
+        assert_eq!(contract.referral_stats_of(alice).referees, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Referrer has reached the maximum number of referees")]
+    fn test_panic_max_referees() {
+        let mut contract = setup_contract();
+        let alice = accounts(1);
+
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+        contract.nft_on_transfer(
+            accounts(0),
+            accounts(2),
+            "2".into(),
+            format!(r#"{{"referrer":"{}"}}"#, alice),
+        );
+        contract.nft_on_transfer(
+            accounts(0),
+            accounts(3),
+            "3".into(),
+            format!(r#"{{"referrer":"{}"}}"#, alice),
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use near_sdk::{near, require};
+
+use crate::Contract;
+
+/// Number of most recent reward ids that are remembered to reject duplicates
+pub const REWARD_ID_RETENTION: u32 = 10_000;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RewardStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+impl Contract {
+    /// Remembers a new reward id as pending. Once `retention` ids are remembered, the oldest id
+    /// is forgotten.
+    pub(crate) fn internal_register_reward_id(&mut self, reward_id: String, retention: u32) {
+        require!(
+            !self.reward_statuses.contains_key(&reward_id),
+            "Reward id already used"
+        );
+
+        let index = self.reward_id_cursor;
+        if index < self.reward_ids.len() {
+            let expired = std::mem::replace(&mut self.reward_ids[index], reward_id.clone());
+            self.reward_statuses.remove(&expired);
+        } else {
+            self.reward_ids.push(reward_id.clone());
+        }
+        self.reward_id_cursor = (index + 1) % retention;
+
+        self.reward_statuses
+            .insert(reward_id, RewardStatus::Pending);
+    }
+
+    pub(crate) fn internal_set_reward_status(&mut self, reward_id: &str, status: RewardStatus) {
+        // the id might have expired already if lots of rewards were sent in the meantime
+        if let Some(current) = self.reward_statuses.get_mut(reward_id) {
+            *current = status;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        AccountId, PromiseResult, RuntimeFeesConfig,
+        json_types::U128,
+        test_utils::{VMContextBuilder, accounts},
+        test_vm_config, testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "Reward id already used")]
+    fn test_panic_reused_reward_id() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator.clone(), vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(operator)
+            .build();
+        testing_env!(context);
+        contract.send_rewards(accounts(1), U128(100), "reward-1".to_string(), None, None);
+        assert_eq!(
+            contract.reward_status("reward-1".to_string()),
+            Some(RewardStatus::Pending)
+        );
+
+        contract.send_rewards(accounts(1), U128(100), "reward-1".to_string(), None, None);
+    }
+
+    #[test]
+    fn test_reward_ids_expire() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft);
+
+        // small enough to fill the ring buffer within the gas limit
+        let retention = 100;
+        for i in 0..=retention {
+            contract.internal_register_reward_id(format!("reward-{i}"), retention);
+        }
+
+        assert_eq!(contract.reward_status("reward-0".to_string()), None);
+        assert_eq!(
+            contract.reward_status("reward-1".to_string()),
+            Some(RewardStatus::Pending)
+        );
+        assert_eq!(contract.reward_ids.len(), retention);
+    }
+
+    #[test]
+    fn test_reward_sent_after_unstake() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator.clone(), vec![], reward_token, nft.clone());
+
+        let mut context = VMContextBuilder::new();
+        testing_env!(context.predecessor_account_id(nft).build());
+        contract.nft_on_transfer(accounts(1), accounts(1), "1".into(), "".into());
+
+        testing_env!(context.predecessor_account_id(operator).build());
+        contract.send_rewards(accounts(1), U128(100), "reward-1".to_string(), None, None);
+
+        // the token is unstaked before the transfer resolves
+        testing_env!(
+            context
+                .predecessor_account_id(context.context.current_account_id.clone())
+                .build()
+        );
+        contract.on_unstake(accounts(1), "1".into());
+        testing_env!(
+            context.build(),
+            test_vm_config(),
+            RuntimeFeesConfig::test(),
+            Default::default(),
+            vec![PromiseResult::Successful(vec![])],
+        );
+        contract.on_reward_sent(
+            "reward-1".to_string(),
+            Some("1".into()),
+            U128(200),
+            Default::default(),
+        );
+
+        assert_eq!(
+            contract.reward_status("reward-1".to_string()),
+            Some(RewardStatus::Sent)
+        );
+        assert_eq!(contract.score_of("1".into()).0, 0);
+        assert_eq!(contract.total_distribute, 200);
+    }
+}
@@ -0,0 +1,329 @@
+use near_contract_standards::{fungible_token::core::ext_ft_core, non_fungible_token::TokenId};
+use near_sdk::{
+    AccountId, NearToken, PromiseResult, assert_one_yocto, env, json_types::U128, near, require,
+};
+
+use crate::{
+    Contract, ContractExt, GAS_FOR_FT_TRANSFER, GAS_FOR_NFT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER,
+    event::RewarderEvent, nft::nft, reason::ScoreReason,
+};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ItemPayout {
+    /// Amount of reward tokens sent to the redeemer
+    RewardToken { amount: U128 },
+    /// NFT owned by the rewarder, sent to the redeemer
+    Nft {
+        contract: AccountId,
+        token_id: TokenId,
+    },
+    /// Fulfilled by the owner outside of the chain
+    OffChain,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct ShopItem {
+    pub name: String,
+    /// Price in score
+    pub price: U128,
+    pub payout: ItemPayout,
+    /// Number of remaining redemptions, unlimited if not set
+    pub stock: Option<u32>,
+}
+
+impl ShopItem {
+    pub(crate) fn assert_valid(&self) {
+        if matches!(self.payout, ItemPayout::Nft { .. }) {
+            require!(self.stock == Some(1), "NFT items need a stock of 1");
+        }
+    }
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RedemptionStatus {
+    Pending,
+    Fulfilled,
+    Failed,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct Redemption {
+    pub item_id: u64,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    pub price: U128,
+    pub payout: ItemPayout,
+    pub timestamp: u64,
+    pub status: RedemptionStatus,
+}
+
+#[near]
+impl Contract {
+    /// Burns score of the caller's primary NFT in exchange for a shop item. Returns the
+    /// redemption id.
+    #[payable]
+    pub fn redeem(&mut self, item_id: u64) -> u64 {
+        assert_one_yocto();
+        let account_id = env::predecessor_account_id();
+        let token_id = self
+            .account_to_token_id
+            .get(&account_id)
+            .cloned()
+            .expect("No NFT staked");
+        // the score of banned tokens is frozen
+        require!(
+            !self.internal_is_banned(&token_id),
+            "Banned tokens can't redeem items"
+        );
+        let mut item = self
+            .shop_items
+            .get(&item_id)
+            .cloned()
+            .expect("Item not found");
+
+        if let Some(stock) = item.stock {
+            require!(stock > 0, "Item is sold out");
+            item.stock = Some(stock - 1);
+            self.shop_items.insert(item_id, item.clone());
+        }
+
+        self.internal_remove_score(token_id.clone(), item.price.0, &ScoreReason::code("redeem"));
+
+        let redemption_id = self.redemption_count;
+        self.redemption_count += 1;
+        let mut redemption_ids = self
+            .account_redemptions
+            .remove(&account_id)
+            .unwrap_or_default();
+        redemption_ids.push(redemption_id);
+        self.account_redemptions
+            .insert(account_id.clone(), redemption_ids);
+
+        let redemption = Redemption {
+            item_id,
+            account_id: account_id.clone(),
+            token_id: token_id.clone(),
+            price: item.price,
+            payout: item.payout,
+            timestamp: env::block_timestamp(),
+            status: RedemptionStatus::Pending,
+        };
+        self.internal_pay_redemption(redemption_id, &redemption);
+        self.redemptions.insert(redemption_id, redemption);
+
+        RewarderEvent::ItemRedeemed {
+            redemption_id,
+            item_id,
+            account_id,
+            token_id,
+            price: item.price,
+        }
+        .emit();
+
+        redemption_id
+    }
+
+    /// Retries the payout of a failed redemption.
+    pub fn retry_redemption(&mut self, redemption_id: u64) {
+        let mut redemption = self
+            .redemptions
+            .get(&redemption_id)
+            .cloned()
+            .expect("Redemption not found");
+        require!(
+            redemption.status == RedemptionStatus::Failed,
+            "Only failed redemptions can be retried"
+        );
+
+        redemption.status = RedemptionStatus::Pending;
+        self.internal_pay_redemption(redemption_id, &redemption);
+        self.redemptions.insert(redemption_id, redemption);
+    }
+
+    #[private]
+    pub fn on_redemption_paid(&mut self, redemption_id: u64) {
+        let redemption = self.redemptions.get_mut(&redemption_id).unwrap();
+
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            redemption.status = RedemptionStatus::Fulfilled;
+
+            RewarderEvent::RedemptionFulfilled { redemption_id }.emit();
+        } else {
+            redemption.status = RedemptionStatus::Failed;
+
+            RewarderEvent::RedemptionFailed { redemption_id }.emit();
+        }
+    }
+}
+
+impl Contract {
+    fn internal_pay_redemption(&self, redemption_id: u64, redemption: &Redemption) {
+        let memo = Some(format!("Redemption {redemption_id}"));
+        let transfer = match &redemption.payout {
+            ItemPayout::RewardToken { amount } => ext_ft_core::ext(self.reward_token.clone())
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .ft_transfer(redemption.account_id.clone(), *amount, memo),
+            ItemPayout::Nft { contract, token_id } => nft::ext(contract.clone())
+                .with_static_gas(GAS_FOR_NFT_TRANSFER)
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .nft_transfer(redemption.account_id.clone(), token_id.clone(), None, memo),
+            // fulfilled by the owner with `fulfill_redemption`
+            ItemPayout::OffChain => return,
+        };
+
+        transfer.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                .on_redemption_paid(redemption_id),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::{
+        fungible_token::FungibleTokenCore, non_fungible_token::core::NonFungibleTokenReceiver,
+    };
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_redeem_burns_score() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        let item_id = contract.add_shop_item(ShopItem {
+            name: "Sticker".to_string(),
+            price: U128(300),
+            payout: ItemPayout::OffChain,
+            stock: Some(1),
+        });
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 1_000);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(alice.clone())
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build();
+        testing_env!(context);
+        let redemption_id = contract.redeem(item_id);
+
+        assert_eq!(contract.ft_balance_of(alice.clone()), U128(700));
+        assert_eq!(contract.ft_total_supply(), U128(700));
+        assert_eq!(contract.get_leaderboard(None)[0].0, U128(700));
+        assert_eq!(contract.redemptions_of(alice)[0].0, redemption_id);
+        assert_eq!(
+            contract.get_redemption(redemption_id).unwrap().status,
+            RedemptionStatus::Pending
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Item is sold out")]
+    fn test_panic_redeem_sold_out() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        let item_id = contract.add_shop_item(ShopItem {
+            name: "Sticker".to_string(),
+            price: U128(300),
+            payout: ItemPayout::OffChain,
+            stock: Some(0),
+        });
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 1_000);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(alice)
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build();
+        testing_env!(context);
+        contract.redeem(item_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "NFT items need a stock of 1")]
+    fn test_panic_nft_item_unlimited_stock() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.add_shop_item(ShopItem {
+            name: "Shit Star".to_string(),
+            price: U128(300),
+            payout: ItemPayout::Nft {
+                contract: nft,
+                token_id: "1".into(),
+            },
+            stock: None,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Banned tokens can't redeem items")]
+    fn test_panic_redeem_banned() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 1_000);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        let item_id = contract.add_shop_item(ShopItem {
+            name: "Sticker".to_string(),
+            price: U128(300),
+            payout: ItemPayout::OffChain,
+            stock: None,
+        });
+        contract.ban_account(alice.clone(), None);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(alice)
+            .attached_deposit(NearToken::from_yoctonear(1))
+            .build();
+        testing_env!(context);
+        contract.redeem(item_id);
+    }
+}
@@ -0,0 +1,152 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{AccountId, env, json_types::U128, near, require};
+
+use crate::{Contract, ContractExt, event::RewarderEvent, reason::ScoreReason};
+
+/// Audit record of a score clawback
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug)]
+pub struct SlashRecord {
+    pub token_id: TokenId,
+    pub amount: U128,
+    pub moderator: AccountId,
+    pub reason: String,
+    pub memo: Option<String>,
+    pub timestamp: u64,
+}
+
+#[near]
+impl Contract {
+    /// Claws back score of a token, e.g. farmed by bots. Returns the remaining score.
+    pub fn slash_score(
+        &mut self,
+        token_id: TokenId,
+        amount: U128,
+        reason: String,
+        memo: Option<String>,
+    ) -> U128 {
+        self.require_moderator();
+
+        U128(self.internal_slash_score(token_id, Some(amount.0), reason, memo))
+    }
+
+    /// Claws back the whole score of a token.
+    pub fn reset_score(&mut self, token_id: TokenId, reason: String, memo: Option<String>) {
+        self.require_moderator();
+
+        self.internal_slash_score(token_id, None, reason, memo);
+    }
+}
+
+impl Contract {
+    fn internal_slash_score(
+        &mut self,
+        token_id: TokenId,
+        amount: Option<u128>,
+        reason: String,
+        memo: Option<String>,
+    ) -> u128 {
+        // accrued points are part of the score that gets clawed back
+        self.internal_settle_points(&token_id);
+
+        let amount = amount.unwrap_or_else(|| *self.scores.get(&token_id).unwrap_or(&0));
+        require!(amount > 0, "Nothing to slash");
+
+        let reason = ScoreReason::new(Some(reason), memo);
+        let score = self.internal_remove_score(token_id.clone(), amount, &reason);
+
+        let moderator = env::predecessor_account_id();
+        let reason_code = reason.code.unwrap();
+        self.slashes.push(SlashRecord {
+            token_id: token_id.clone(),
+            amount: U128(amount),
+            moderator: moderator.clone(),
+            reason: reason_code.clone(),
+            memo: reason.memo.clone(),
+            timestamp: env::block_timestamp(),
+        });
+
+        RewarderEvent::ScoreSlashed {
+            token_id,
+            amount: U128(amount),
+            score: U128(score),
+            moderator,
+            reason: reason_code,
+            memo: reason.memo,
+        }
+        .emit();
+
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    fn setup_contract() -> Contract {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.internal_record_score("2".into(), 200);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.add_moderator(accounts(3));
+
+        contract
+    }
+
+    #[test]
+    fn test_slash_and_reset_score() {
+        let mut contract = setup_contract();
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(3))
+            .build();
+        testing_env!(context);
+        assert_eq!(
+            contract.slash_score("1".into(), U128(150), "bot".to_string(), None),
+            U128(150)
+        );
+        assert_eq!(contract.get_leaderboard(None)[0].1[0].0, "2".to_string());
+
+        contract.reset_score("2".into(), "bot".to_string(), Some("farmed".to_string()));
+        assert_eq!(contract.score_of("2".into()), U128(0));
+        assert_eq!(contract.total_score, 150);
+        assert_eq!(contract.total_staked_score, 150);
+
+        let slashes = contract.get_slashes(None, None);
+        assert_eq!(slashes.len(), 2);
+        assert_eq!(slashes[1].token_id, "2".to_string());
+        assert_eq!(slashes[1].amount, U128(200));
+        assert_eq!(slashes[1].moderator, accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only moderators can call this function")]
+    fn test_panic_non_moderator_slashes_score() {
+        let mut contract = setup_contract();
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .build();
+        testing_env!(context);
+        contract.slash_score("2".into(), U128(100), "bot".to_string(), None);
+    }
+}
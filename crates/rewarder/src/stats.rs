@@ -0,0 +1,72 @@
+use near_sdk::{AccountId, json_types::U128, near};
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Eq)]
+pub struct ConfigView {
+    pub owner: AccountId,
+    pub operator: AccountId,
+    pub whitelisted_record_score_ids: Vec<AccountId>,
+    pub moderators: Vec<AccountId>,
+    pub reward_token: AccountId,
+    pub nft: AccountId,
+}
+
+#[near(serializers = [json])]
+#[derive(Debug, PartialEq, Eq)]
+pub struct StatsView {
+    pub total_score: U128,
+    /// Score of all currently staked tokens
+    pub total_staked_score: U128,
+    pub total_distribute: U128,
+    pub total_donation: U128,
+    pub total_nft_staked: U128,
+    /// Number of distinct tokens with a score
+    pub scored_tokens: u64,
+    pub average_score: U128,
+    pub top_score: U128,
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+    use crate::Contract;
+
+    #[test]
+    fn test_get_stats() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.nft_on_transfer(accounts(0), accounts(3), "3".into(), "".into());
+        contract.internal_record_score("1".into(), 300);
+        contract.internal_record_score("2".into(), 100);
+        contract.internal_record_score("2".into(), 100);
+
+        assert_eq!(
+            contract.get_stats(),
+            StatsView {
+                total_score: U128(500),
+                total_staked_score: U128(500),
+                total_distribute: U128(0),
+                total_donation: U128(0),
+                total_nft_staked: U128(3),
+                scored_tokens: 2,
+                average_score: U128(250),
+                top_score: U128(300),
+            }
+        );
+    }
+}
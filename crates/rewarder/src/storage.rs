@@ -0,0 +1,100 @@
+use crate::{Contract, ContractExt};
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::{AccountId, NearToken, Promise, assert_one_yocto, env, near_bindgen, require};
+
+/// Shit Stars need no registration, because scores are stored per NFT and paid for by the
+/// rewarder. Every account is reported as registered with an empty storage balance.
+const EMPTY_STORAGE_BALANCE: StorageBalance = StorageBalance {
+    total: NearToken::from_yoctonear(0),
+    available: NearToken::from_yoctonear(0),
+};
+
+#[near_bindgen]
+impl StorageManagement for Contract {
+    /// Refunds the attached deposit, since no storage needs to be paid for.
+    #[payable]
+    #[allow(unused_variables)]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let deposit = env::attached_deposit();
+        if !deposit.is_zero() {
+            Promise::new(env::predecessor_account_id()).transfer(deposit);
+        }
+
+        EMPTY_STORAGE_BALANCE
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<NearToken>) -> StorageBalance {
+        assert_one_yocto();
+        require!(
+            amount.is_none_or(|amount| amount.is_zero()),
+            "The amount is greater than the available storage balance"
+        );
+
+        EMPTY_STORAGE_BALANCE
+    }
+
+    /// Accounts are registered implicitly, so they can't be unregistered.
+    #[payable]
+    #[allow(unused_variables)]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+
+        false
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: NearToken::from_yoctonear(0),
+            max: Some(NearToken::from_yoctonear(0)),
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        Some(EMPTY_STORAGE_BALANCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_storage_deposit_is_refunded() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .attached_deposit(NearToken::from_millinear(125))
+            .build();
+        testing_env!(context);
+        let balance = contract.storage_deposit(None, None);
+
+        assert_eq!(balance.total, NearToken::from_yoctonear(0));
+        assert_eq!(
+            contract.storage_balance_of(accounts(2)).unwrap().available,
+            NearToken::from_yoctonear(0)
+        );
+        assert_eq!(
+            contract.storage_balance_bounds().max,
+            Some(NearToken::from_yoctonear(0))
+        );
+    }
+}
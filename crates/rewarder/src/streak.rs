@@ -0,0 +1,137 @@
+use near_contract_standards::non_fungible_token::TokenId;
+use near_sdk::{env, near};
+use primitive_types::U256;
+
+use crate::{BPS_DENOMINATOR, Contract, accrual::DAY, event::RewarderEvent};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreakTier {
+    /// Consecutive days of activity needed to reach this tier
+    pub min_days: u32,
+    /// Score multiplier in basis points, 10_000 being 1x
+    pub multiplier_bps: u16,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Default)]
+pub struct Streak {
+    pub days: u32,
+    /// Day bucket of the last score, as `block_timestamp / DAY`
+    pub last_day: u64,
+}
+
+#[near(serializers = [json])]
+pub struct StreakView {
+    /// Current streak, which is 0 if it already broke
+    pub days: u32,
+    pub last_day: u64,
+    pub multiplier_bps: u16,
+}
+
+impl Contract {
+    /// Continues or resets the streak of a token and returns the boosted score amount.
+    pub(crate) fn internal_apply_streak(&mut self, token_id: &TokenId, amount: u128) -> u128 {
+        let today = env::block_timestamp() / DAY;
+        let mut streak = self.streaks.get(token_id).copied().unwrap_or_default();
+
+        if streak.days == 0 || streak.last_day != today {
+            streak.days = if streak.days > 0 && streak.last_day + 1 == today {
+                streak.days + 1
+            } else {
+                1
+            };
+            streak.last_day = today;
+            self.streaks.insert(token_id.clone(), streak);
+
+            RewarderEvent::StreakUpdated {
+                token_id: token_id.clone(),
+                days: streak.days,
+            }
+            .emit();
+        }
+
+        let multiplier_bps = self.internal_streak_multiplier(streak.days);
+        (U256::from(amount) * U256::from(multiplier_bps) / U256::from(BPS_DENOMINATOR)).as_u128()
+    }
+
+    pub(crate) fn internal_streak_of(&self, token_id: &TokenId) -> StreakView {
+        let today = env::block_timestamp() / DAY;
+        let streak = self.streaks.get(token_id).copied().unwrap_or_default();
+        let days = if streak.last_day + 1 >= today {
+            streak.days
+        } else {
+            0
+        };
+
+        StreakView {
+            days,
+            last_day: streak.last_day,
+            multiplier_bps: self.internal_streak_multiplier(days) as u16,
+        }
+    }
+
+    fn internal_streak_multiplier(&self, days: u32) -> u128 {
+        self.streak_tiers
+            .iter()
+            .rev()
+            .find(|tier| tier.min_days <= days)
+            .map_or(BPS_DENOMINATOR, |tier| u128::from(tier.multiplier_bps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        AccountId,
+        json_types::U128,
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_streak_multiplier() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao.clone(), operator, vec![], reward_token, nft.clone());
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_streak_tiers(vec![
+            StreakTier {
+                min_days: 2,
+                multiplier_bps: 15_000,
+            },
+            StreakTier {
+                min_days: 3,
+                multiplier_bps: 20_000,
+            },
+        ]);
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+
+        for (day, expected) in [(0, 100), (0, 200), (1, 350), (2, 550), (4, 650)] {
+            let context = VMContextBuilder::new().block_timestamp(day * DAY).build();
+            testing_env!(context);
+            contract.internal_record_score("1".into(), 100);
+            assert_eq!(contract.score_of("1".into()), U128(expected));
+        }
+
+        // the streak broke on day 3, so it started again on day 4
+        let streak = contract.streak_of("1".into());
+        assert_eq!(streak.days, 1);
+        assert_eq!(streak.multiplier_bps, 10_000);
+
+        let context = VMContextBuilder::new().block_timestamp(7 * DAY).build();
+        testing_env!(context);
+        assert_eq!(contract.streak_of("1".into()).days, 0);
+    }
+}
@@ -0,0 +1,344 @@
+use near_contract_standards::{fungible_token::core::ext_ft_core, non_fungible_token::TokenId};
+use near_sdk::{
+    AccountId, NearToken, Promise, PromiseResult, env, json_types::U128, near, require,
+};
+use primitive_types::U256;
+
+use crate::{
+    Contract, ContractExt, GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER, event::RewarderEvent,
+};
+
+/// Precision of the reward per share accumulator. With equal weighting every staked token owns
+/// exactly this many shares.
+pub const ACC_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamWeighting {
+    /// Rewards are shared proportional to the score of each staked token
+    Score,
+    /// Every staked token receives the same share
+    Equal,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct RewardStream {
+    pub rate_per_second: U128,
+    pub weighting: StreamWeighting,
+    /// Funded reward tokens that have not been streamed yet
+    pub remaining: U128,
+    pub reward_per_share: U128,
+    /// Last time the accumulator was updated, in nanoseconds
+    pub last_update: u64,
+}
+
+#[near(serializers = [json])]
+pub struct RewardStreamView {
+    pub rate_per_second: U128,
+    pub weighting: StreamWeighting,
+    pub remaining: U128,
+    /// Seconds until the funded rewards run out at the current rate
+    pub runway_seconds: Option<u64>,
+}
+
+impl RewardStream {
+    pub(crate) fn accumulated(&self, total_shares: u128, now: u64) -> (u128, u128) {
+        if total_shares == 0 || now <= self.last_update {
+            return (self.reward_per_share.0, 0);
+        }
+
+        let elapsed = U256::from(now - self.last_update);
+        let reward = (U256::from(self.rate_per_second.0) * elapsed / U256::from(1_000_000_000u64))
+            .min(U256::from(self.remaining.0));
+        let reward_per_share = U256::from(self.reward_per_share.0)
+            + reward * U256::from(ACC_PRECISION) / U256::from(total_shares);
+
+        (reward_per_share.as_u128(), reward.as_u128())
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn claim_staking_rewards(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        if let Some(token_id) = self.account_to_token_id.get(&account_id).cloned() {
+            self.internal_settle_stream(&token_id);
+            self.internal_reset_stream_debt(&token_id);
+        }
+
+        self.internal_claim_staking_rewards(account_id)
+            .expect("No staking rewards to claim")
+    }
+
+    #[private]
+    pub fn on_staking_rewards_claimed(&mut self, account_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        let pending = *self.stream_pending.get(&account_id).unwrap_or(&0);
+        self.stream_pending
+            .insert(account_id.clone(), pending + amount.0);
+        self.stream_unclaimed += amount.0;
+
+        RewarderEvent::StakingRewardsClaimFailed { account_id, amount }.emit();
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_fund_reward_stream(&mut self, sender_id: AccountId, amount: u128) {
+        require!(
+            sender_id == self.owner,
+            "Only owner can fund the reward stream"
+        );
+
+        self.internal_update_stream();
+        let stream = self
+            .reward_stream
+            .as_mut()
+            .expect("Reward stream not configured");
+        stream.remaining = U128(stream.remaining.0 + amount);
+
+        RewarderEvent::RewardStreamFunded {
+            amount: U128(amount),
+            remaining: stream.remaining,
+        }
+        .emit();
+    }
+
+    pub(crate) fn internal_stream_shares_of(&self, token_id: &TokenId) -> u128 {
+        let Some(stream) = &self.reward_stream else {
+            return 0;
+        };
+        if !self.token_id_to_account.contains_key(token_id) {
+            return 0;
+        }
+
+        match stream.weighting {
+            StreamWeighting::Score => *self.scores.get(token_id).unwrap_or(&0),
+            StreamWeighting::Equal => ACC_PRECISION,
+        }
+    }
+
+    pub(crate) fn internal_total_stream_shares(&self) -> u128 {
+        match self.reward_stream.as_ref().map(|stream| stream.weighting) {
+            Some(StreamWeighting::Score) => self.total_staked_score,
+            Some(StreamWeighting::Equal) => self.total_nft_staked * ACC_PRECISION,
+            None => 0,
+        }
+    }
+
+    pub(crate) fn internal_update_stream(&mut self) {
+        let total_shares = self.internal_total_stream_shares();
+        let Some(stream) = self.reward_stream.as_mut() else {
+            return;
+        };
+
+        let now = env::block_timestamp();
+        let (reward_per_share, reward) = stream.accumulated(total_shares, now);
+        stream.reward_per_share = U128(reward_per_share);
+        stream.remaining = U128(stream.remaining.0 - reward);
+        stream.last_update = now;
+        self.stream_unclaimed += reward;
+    }
+
+    /// Credits the streamed rewards of a token to its staker. Needs to be called before the
+    /// shares of the token or the total shares change.
+    pub(crate) fn internal_settle_stream(&mut self, token_id: &TokenId) {
+        if self.reward_stream.is_none() {
+            return;
+        }
+        self.internal_update_stream();
+
+        let Some(account_id) = self.token_id_to_account.get(token_id).cloned() else {
+            return;
+        };
+        let accrued = self.internal_stream_accrued(token_id);
+        if self.internal_is_banned(token_id) {
+            // banned tokens forfeit their rewards, which are streamed again
+            if let Some(stream) = self.reward_stream.as_mut() {
+                stream.remaining = U128(stream.remaining.0 + accrued);
+            }
+            self.stream_unclaimed = self.stream_unclaimed.saturating_sub(accrued);
+        } else if accrued > 0 {
+            let pending = *self.stream_pending.get(&account_id).unwrap_or(&0);
+            self.stream_pending.insert(account_id, pending + accrued);
+        }
+    }
+
+    /// Resets the reward debt of a token after its shares changed.
+    pub(crate) fn internal_reset_stream_debt(&mut self, token_id: &TokenId) {
+        let Some(stream) = &self.reward_stream else {
+            return;
+        };
+
+        let shares = self.internal_stream_shares_of(token_id);
+        let debt = (U256::from(shares) * U256::from(stream.reward_per_share.0)
+            / U256::from(ACC_PRECISION))
+        .as_u128();
+        self.stream_debts.insert(token_id.clone(), debt);
+    }
+
+    pub(crate) fn internal_claim_staking_rewards(
+        &mut self,
+        account_id: AccountId,
+    ) -> Option<Promise> {
+        let amount = self
+            .stream_pending
+            .remove(&account_id)
+            .filter(|amount| *amount > 0)?;
+        // rounding keeps the claimed rewards below the streamed ones
+        self.stream_unclaimed = self.stream_unclaimed.saturating_sub(amount);
+
+        RewarderEvent::StakingRewardsClaimed {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        Some(
+            ext_ft_core::ext(self.reward_token.clone())
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .ft_transfer(
+                    account_id.clone(),
+                    U128(amount),
+                    Some("Staking rewards".to_string()),
+                )
+                .then(
+                    Self::ext(env::current_account_id())
+                        .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                        .on_staking_rewards_claimed(account_id, U128(amount)),
+                ),
+        )
+    }
+
+    fn internal_stream_accrued(&self, token_id: &TokenId) -> u128 {
+        let Some(stream) = &self.reward_stream else {
+            return 0;
+        };
+
+        let shares = self.internal_stream_shares_of(token_id);
+        let earned = (U256::from(shares) * U256::from(stream.reward_per_share.0)
+            / U256::from(ACC_PRECISION))
+        .as_u128();
+
+        earned.saturating_sub(*self.stream_debts.get(token_id).unwrap_or(&0))
+    }
+
+    pub(crate) fn internal_pending_staking_rewards(&self, account_id: &AccountId) -> u128 {
+        let pending = *self.stream_pending.get(account_id).unwrap_or(&0);
+        let (Some(stream), Some(token_id)) = (
+            &self.reward_stream,
+            self.account_to_token_id.get(account_id),
+        ) else {
+            return pending;
+        };
+        if self.internal_is_banned(token_id) {
+            return pending;
+        }
+
+        let total_shares = self.internal_total_stream_shares();
+        let (reward_per_share, _) = stream.accumulated(total_shares, env::block_timestamp());
+        let shares = self.internal_stream_shares_of(token_id);
+        let earned = (U256::from(shares) * U256::from(reward_per_share)
+            / U256::from(ACC_PRECISION))
+        .as_u128();
+
+        pending + earned.saturating_sub(*self.stream_debts.get(token_id).unwrap_or(&0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::{
+        fungible_token::receiver::FungibleTokenReceiver,
+        non_fungible_token::core::NonFungibleTokenReceiver,
+    };
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn setup_contract(weighting: StreamWeighting) -> Contract {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator,
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(dao.clone())
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.configure_reward_stream(U128(10), weighting);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(dao, U128(1_000), r#"{"fund_reward_stream":true}"#.into());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft)
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), accounts(1), "1".into(), "".into());
+        contract.internal_record_score("1".into(), 100);
+        contract.nft_on_transfer(accounts(0), accounts(2), "2".into(), "".into());
+        contract.internal_record_score("2".into(), 300);
+
+        contract
+    }
+
+    #[test]
+    fn test_stream_weighted_by_score() {
+        let contract = setup_contract(StreamWeighting::Score);
+
+        let context = VMContextBuilder::new().block_timestamp(10 * SECOND).build();
+        testing_env!(context);
+
+        assert_eq!(contract.pending_staking_rewards(accounts(1)), U128(25));
+        assert_eq!(contract.pending_staking_rewards(accounts(2)), U128(75));
+        assert_eq!(
+            contract.get_reward_stream().unwrap().runway_seconds,
+            Some(90)
+        );
+    }
+
+    #[test]
+    fn test_stream_shared_equally_and_claimed_on_unstake() {
+        let mut contract = setup_contract(StreamWeighting::Equal);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(accounts(1))
+            .block_timestamp(10 * SECOND)
+            .build();
+        testing_env!(context);
+        assert_eq!(contract.pending_staking_rewards(accounts(1)), U128(50));
+
+        contract.on_unstake(accounts(1), "1".into());
+        assert_eq!(contract.pending_staking_rewards(accounts(1)), U128(0));
+
+        // Alice no longer takes part in the stream
+        let context = VMContextBuilder::new().block_timestamp(20 * SECOND).build();
+        testing_env!(context);
+        assert_eq!(contract.pending_staking_rewards(accounts(1)), U128(0));
+        assert_eq!(contract.pending_staking_rewards(accounts(2)), U128(150));
+    }
+}
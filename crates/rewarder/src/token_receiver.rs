@@ -2,14 +2,12 @@ use near_contract_standards::{
     fungible_token::receiver::FungibleTokenReceiver,
     non_fungible_token::{TokenId, core::NonFungibleTokenReceiver},
 };
-use near_sdk::{AccountId, PromiseOrValue, env, json_types::U128, near};
-use primitive_types::U256;
+use near_sdk::{AccountId, PromiseOrValue, env, json_types::U128, near, serde_json};
 
-use crate::{Contract, ContractExt};
+use crate::{Contract, ContractExt, donation::DonationMsg, referral::StakeMsg};
 
 #[near]
 impl FungibleTokenReceiver for Contract {
-    #[allow(unused_variables)]
     fn ft_on_transfer(
         &mut self,
         sender_id: AccountId,
@@ -21,10 +19,34 @@ impl FungibleTokenReceiver for Contract {
             "Only receive tokens from reward token"
         );
 
-        if let Some(token_id) = self.account_to_token_id.get(&sender_id) {
+        let donation_msg: DonationMsg = if msg.is_empty() {
+            DonationMsg::default()
+        } else {
+            serde_json::from_str(&msg).expect("Invalid msg")
+        };
+
+        if let Some(season) = donation_msg.prize_pool {
+            self.internal_fund_prize_pool(sender_id, season, amount.0);
+        } else if donation_msg.pro_rata_distribution {
+            self.internal_start_pro_rata_distribution(sender_id, amount.0);
+        } else if donation_msg.fund_reward_stream {
+            self.internal_fund_reward_stream(sender_id, amount.0);
+        } else if let Some(token_id) = donation_msg.donate_to_token {
+            if !self.internal_donate_to_token(sender_id, token_id, amount.0) {
+                return PromiseOrValue::Value(amount);
+            }
+        } else if let Some(token_id) = self.account_to_token_id.get(&sender_id).cloned() {
+            // banned stakers can't earn score with donations
+            if self.internal_is_banned(&token_id) {
+                return PromiseOrValue::Value(amount);
+            }
             self.internal_record_score(token_id.clone(), amount.0 * 4);
-            self.total_donation =
-                (U256::from(self.total_donation) + U256::from(amount.0)).as_u128();
+            self.internal_record_donation(sender_id, token_id, amount.0);
+        } else if donation_msg.escrow {
+            self.internal_escrow_donation(sender_id, amount.0);
+        } else {
+            // refund donations from accounts without staked NFT
+            return PromiseOrValue::Value(amount);
         }
 
         PromiseOrValue::Value(U128(0))
@@ -46,13 +68,37 @@ impl NonFungibleTokenReceiver for Contract {
             "Only receive tokens from NFT contract"
         );
 
+        // imported stakes are activated once the staker or the owner transfers the NFT. Anyone
+        // else who got hold of it in the meantime stakes it for themselves instead.
+        let previous_owner_id = match self.imported_stakers.remove(&token_id) {
+            Some(staker) if previous_owner_id == staker || previous_owner_id == self.owner => {
+                staker
+            }
+            _ => previous_owner_id,
+        };
+
+        // return NFTs of banned accounts and banned NFTs
+        if self.banned_accounts.contains(&previous_owner_id)
+            || self.banned_tokens.contains(&token_id)
+        {
+            return PromiseOrValue::Value(true);
+        }
+
         assert!(
             self.account_to_token_id.get(&previous_owner_id).is_none(),
             "User already has already staked"
         );
 
+        // wallets might send arbitrary messages
+        let stake_msg: StakeMsg = serde_json::from_str(&msg).unwrap_or_default();
+
         self.internal_on_stake(previous_owner_id.clone(), token_id.clone());
 
+        if let Some(referrer) = stake_msg.referrer {
+            self.internal_register_referral(previous_owner_id.clone(), referrer);
+        }
+        self.internal_release_escrow(previous_owner_id, token_id);
+
         PromiseOrValue::Value(false)
     }
 }
@@ -63,6 +109,7 @@ mod tests {
     use near_sdk::{NearToken, testing_env};
 
     use super::*;
+    use crate::donation::DonationSplit;
 
     #[test]
     fn test_ft_on_transfer_with_nft_quadruple_score() {
@@ -97,6 +144,221 @@ mod tests {
         assert_eq!(score.0, &amount * 4);
     }
 
+    #[test]
+    fn test_ft_on_transfer_tracks_donor_leaderboard() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+        let bob = accounts(2);
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft.clone());
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".to_string(), "".to_string());
+        contract.nft_on_transfer(accounts(0), bob.clone(), "2".to_string(), "".to_string());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(alice.clone(), U128(100), "".to_string());
+        contract.ft_on_transfer(bob.clone(), U128(150), "".to_string());
+        contract.ft_on_transfer(alice.clone(), U128(100), "".to_string());
+
+        assert_eq!(contract.total_donation, 350);
+        assert_eq!(contract.donation_of(alice.clone()), U128(200));
+        assert_eq!(
+            contract.get_donor_leaderboard(None, None),
+            vec![(U128(200), vec![alice]), (U128(150), vec![bob.clone()])]
+        );
+        assert_eq!(
+            contract.get_donor_leaderboard(Some(1), Some(1)),
+            vec![(U128(150), vec![bob])]
+        );
+    }
+
+    #[test]
+    fn test_ft_on_transfer_refunds_non_staker() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft.clone());
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+
+        let refund = contract.ft_on_transfer(alice.clone(), U128(100), "".to_string());
+        assert!(matches!(refund, PromiseOrValue::Value(U128(100))));
+        assert_eq!(contract.total_donation, 0);
+        assert_eq!(contract.escrowed_donation_of(alice), U128(0));
+    }
+
+    #[test]
+    fn test_ft_on_transfer_escrow_released_on_stake() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft.clone());
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+
+        let refund =
+            contract.ft_on_transfer(alice.clone(), U128(100), r#"{"escrow":true}"#.to_string());
+        assert!(matches!(refund, PromiseOrValue::Value(U128(0))));
+        assert_eq!(contract.escrowed_donation_of(alice.clone()), U128(100));
+        assert_eq!(contract.total_escrowed, 100);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".to_string(), "".to_string());
+
+        assert_eq!(contract.escrowed_donation_of(alice.clone()), U128(0));
+        assert_eq!(contract.total_escrowed, 0);
+        assert_eq!(contract.total_donation, 100);
+        assert_eq!(contract.donation_of(alice), U128(100));
+        assert_eq!(contract.score_of("1".to_string()), U128(400));
+    }
+
+    #[test]
+    fn test_withdraw_escrow() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft);
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token)
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(alice.clone(), U128(100), r#"{"escrow":true}"#.to_string());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(alice.clone())
+            .build();
+        testing_env!(context);
+        contract.withdraw_escrow();
+
+        assert_eq!(contract.escrowed_donation_of(alice), U128(0));
+        assert_eq!(contract.total_escrowed, 0);
+        assert_eq!(contract.total_donation, 0);
+    }
+
+    #[test]
+    fn test_ft_on_transfer_donate_to_token() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+        let bob = accounts(2);
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft.clone());
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".to_string(), "".to_string());
+
+        // Bob has no staked NFT, but can still boost Alice's NFT
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        let refund = contract.ft_on_transfer(
+            bob.clone(),
+            U128(100),
+            r#"{"donate_to_token":"1"}"#.to_string(),
+        );
+        assert!(matches!(refund, PromiseOrValue::Value(U128(0))));
+
+        assert_eq!(contract.score_of("1".to_string()), U128(400));
+        assert_eq!(contract.donation_of(bob), U128(100));
+        assert_eq!(contract.donation_of(alice), U128(0));
+        assert_eq!(contract.received_donations_of("1".to_string()), U128(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "Token is not staked")]
+    fn test_ft_on_transfer_donate_to_unstaked_token() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+
+        let mut contract = Contract::new(dao, operator, vec![], reward_token.clone(), nft.clone());
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(
+            accounts(1),
+            U128(100),
+            r#"{"donate_to_token":"1"}"#.to_string(),
+        );
+    }
+
+    #[test]
+    fn test_ft_on_transfer_splits_donation() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator,
+            vec![],
+            reward_token.clone(),
+            nft.clone(),
+        );
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(dao.clone())
+            .build();
+        testing_env!(context);
+        contract.set_donation_split(DonationSplit {
+            treasury: Some(dao),
+            treasury_bps: 2_000,
+            burn_account: Some("burn".parse().unwrap()),
+            burn_bps: 500,
+        });
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(nft.clone())
+            .build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".to_string(), "".to_string());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(reward_token.clone())
+            .build();
+        testing_env!(context);
+        contract.ft_on_transfer(alice, U128(1_000), "".to_string());
+
+        let totals = contract.get_donation_split_totals();
+        assert_eq!(totals.treasury, U128(200));
+        assert_eq!(totals.burned, U128(50));
+        assert_eq!(totals.recycled, U128(750));
+        assert_eq!(contract.score_of("1".to_string()), U128(4_000));
+    }
+
     #[test]
     #[should_panic]
     fn test_nft_on_transfer_incorrect_nft() {
@@ -0,0 +1,336 @@
+use near_contract_standards::fungible_token::core::ext_ft_core;
+use near_sdk::{
+    AccountId, NearToken, Promise, PromiseResult, env, json_types::U128, near, require,
+};
+use primitive_types::U256;
+
+use crate::{
+    Contract, ContractExt, GAS_FOR_FT_TRANSFER, GAS_FOR_RESOLVE_TRANSFER, accrual::DAY,
+    event::RewarderEvent,
+};
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone)]
+pub struct VestingConfig {
+    pub cliff_seconds: u64,
+    pub duration_seconds: u64,
+}
+
+#[near(serializers = [borsh, json])]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub amount: U128,
+    pub withdrawn: U128,
+    /// Start of the vesting in nanoseconds
+    pub start: u64,
+    pub cliff_seconds: u64,
+    pub duration_seconds: u64,
+}
+
+impl VestingSchedule {
+    pub fn vested(&self, now: u64) -> u128 {
+        let elapsed = now.saturating_sub(self.start) / 1_000_000_000;
+        if elapsed < self.cliff_seconds {
+            0
+        } else if elapsed >= self.duration_seconds {
+            self.amount.0
+        } else {
+            (U256::from(self.amount.0) * U256::from(elapsed) / U256::from(self.duration_seconds))
+                .as_u128()
+        }
+    }
+
+    pub fn withdrawable(&self, now: u64) -> u128 {
+        self.vested(now) - self.withdrawn.0
+    }
+
+    fn is_fully_vested(&self, now: u64) -> bool {
+        self.vested(now) == self.amount.0
+    }
+}
+
+#[near]
+impl Contract {
+    pub fn withdraw_vested(&mut self) -> Promise {
+        let account_id = env::predecessor_account_id();
+        let now = env::block_timestamp();
+
+        let mut schedules = self
+            .vesting_schedules
+            .remove(&account_id)
+            .expect("No vesting schedules found");
+        let mut amount = 0;
+        for schedule in schedules.iter_mut() {
+            let withdrawable = schedule.withdrawable(now);
+            schedule.withdrawn = U128(schedule.withdrawn.0 + withdrawable);
+            amount += withdrawable;
+        }
+        require!(amount > 0, "Nothing vested yet");
+
+        schedules.retain(|schedule| schedule.withdrawn != schedule.amount);
+        if !schedules.is_empty() {
+            self.vesting_schedules.insert(account_id.clone(), schedules);
+        }
+        self.total_vesting -= amount;
+
+        RewarderEvent::VestedWithdrawn {
+            account_id: account_id.clone(),
+            amount: U128(amount),
+        }
+        .emit();
+
+        ext_ft_core::ext(self.reward_token.clone())
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .ft_transfer(
+                account_id.clone(),
+                U128(amount),
+                Some("Vested rewards".to_string()),
+            )
+            .then(
+                Self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_vested_withdrawn(account_id, U128(amount)),
+            )
+    }
+
+    #[private]
+    pub fn on_vested_withdrawn(&mut self, account_id: AccountId, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        // the failed amount is already vested, so it can be withdrawn again right away
+        let mut schedules = self
+            .vesting_schedules
+            .remove(&account_id)
+            .unwrap_or_default();
+        schedules.push(VestingSchedule {
+            amount,
+            withdrawn: U128(0),
+            start: env::block_timestamp(),
+            cliff_seconds: 0,
+            duration_seconds: 0,
+        });
+        fold_vested_schedules(&mut schedules, env::block_timestamp());
+        self.vesting_schedules.insert(account_id.clone(), schedules);
+        self.total_vesting += amount.0;
+
+        RewarderEvent::VestedWithdrawFailed { account_id, amount }.emit();
+    }
+}
+
+impl Contract {
+    /// Vests an amount for an account. Rewards of the same day share one schedule, which starts
+    /// with the latest of them so that no reward vests before its cliff, and fully vested
+    /// schedules are folded into one. This keeps the number of schedules per account bounded by
+    /// the vesting duration in days.
+    pub(crate) fn internal_create_vesting_schedule(
+        &mut self,
+        account_id: AccountId,
+        amount: u128,
+        config: &VestingConfig,
+    ) {
+        let now = env::block_timestamp();
+        let schedule = VestingSchedule {
+            amount: U128(amount),
+            withdrawn: U128(0),
+            start: now,
+            cliff_seconds: config.cliff_seconds,
+            duration_seconds: config.duration_seconds,
+        };
+
+        let mut schedules = self
+            .vesting_schedules
+            .remove(&account_id)
+            .unwrap_or_default();
+        fold_vested_schedules(&mut schedules, now);
+        // moving the start of a schedule that was withdrawn from could undercut the withdrawn amount
+        match schedules.iter_mut().find(|existing| {
+            existing.start / DAY == now / DAY
+                && existing.withdrawn.0 == 0
+                && existing.cliff_seconds == schedule.cliff_seconds
+                && existing.duration_seconds == schedule.duration_seconds
+        }) {
+            Some(existing) => {
+                existing.amount = U128(existing.amount.0 + amount);
+                existing.start = now;
+            }
+            None => schedules.push(schedule.clone()),
+        }
+        self.vesting_schedules.insert(account_id.clone(), schedules);
+        self.total_vesting += amount;
+
+        RewarderEvent::VestingScheduleCreated {
+            account_id,
+            amount: schedule.amount,
+            start: schedule.start,
+            cliff_seconds: schedule.cliff_seconds,
+            duration_seconds: schedule.duration_seconds,
+        }
+        .emit();
+    }
+}
+
+/// Merges all fully vested schedules into a single one, which can be withdrawn right away.
+fn fold_vested_schedules(schedules: &mut Vec<VestingSchedule>, now: u64) {
+    let (vested, mut vesting): (Vec<_>, Vec<_>) = schedules
+        .drain(..)
+        .partition(|schedule| schedule.is_fully_vested(now));
+    let remaining: u128 = vested
+        .iter()
+        .map(|schedule| schedule.amount.0 - schedule.withdrawn.0)
+        .sum();
+    if remaining > 0 {
+        vesting.push(VestingSchedule {
+            amount: U128(remaining),
+            withdrawn: U128(0),
+            start: now,
+            cliff_seconds: 0,
+            duration_seconds: 0,
+        });
+    }
+    *schedules = vesting;
+}
+
+#[cfg(test)]
+mod tests {
+    use near_contract_standards::non_fungible_token::core::NonFungibleTokenReceiver;
+    use near_sdk::{
+        test_utils::{VMContextBuilder, accounts},
+        testing_env,
+    };
+
+    use super::*;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_vesting_send_rewards() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(
+            dao.clone(),
+            operator.clone(),
+            vec![],
+            reward_token,
+            nft.clone(),
+        );
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_vesting_config(Some(VestingConfig {
+            cliff_seconds: 10,
+            duration_seconds: 100,
+        }));
+
+        let context = VMContextBuilder::new().predecessor_account_id(nft).build();
+        testing_env!(context);
+        contract.nft_on_transfer(accounts(0), alice.clone(), "1".into(), "".into());
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(operator)
+            .block_timestamp(0)
+            .build();
+        testing_env!(context);
+        contract.send_rewards(alice.clone(), U128(500), "reward-1".to_string(), None, None);
+
+        // score is recorded up front
+        assert_eq!(contract.score_of("1".into()), U128(1_000));
+        assert_eq!(contract.get_total_vesting_liabilities(), U128(1_000));
+        let schedules = contract.vesting_schedules_of(alice.clone());
+        assert_eq!(schedules.len(), 1);
+
+        let schedule = &schedules[0];
+        assert_eq!(schedule.vested(5 * SECOND), 0);
+        assert_eq!(schedule.vested(10 * SECOND), 100);
+        assert_eq!(schedule.vested(200 * SECOND), 1_000);
+
+        let context = VMContextBuilder::new()
+            .predecessor_account_id(alice.clone())
+            .block_timestamp(40 * SECOND)
+            .build();
+        testing_env!(context);
+        contract.withdraw_vested();
+
+        assert_eq!(contract.get_total_vesting_liabilities(), U128(600));
+        assert_eq!(contract.vesting_schedules_of(alice)[0].withdrawn, U128(400));
+    }
+
+    #[test]
+    fn test_vesting_schedules_merged() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao.clone(), operator.clone(), vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_vesting_config(Some(VestingConfig {
+            cliff_seconds: 0,
+            duration_seconds: 100,
+        }));
+
+        for (i, timestamp) in [DAY, DAY + 10 * SECOND, 3 * DAY].into_iter().enumerate() {
+            let context = VMContextBuilder::new()
+                .predecessor_account_id(operator.clone())
+                .block_timestamp(timestamp)
+                .build();
+            testing_env!(context);
+            contract.send_rewards(alice.clone(), U128(100), format!("reward-{i}"), None, None);
+        }
+
+        // rewards of the first day are fully vested and folded
+        let schedules = contract.vesting_schedules_of(alice);
+        assert_eq!(schedules.len(), 2);
+        assert_eq!(schedules[0].amount, U128(200));
+        assert_eq!(schedules[0].duration_seconds, 0);
+        assert_eq!(schedules[1].amount, U128(100));
+        assert_eq!(schedules[1].start, 3 * DAY);
+        assert_eq!(contract.get_total_vesting_liabilities(), U128(300));
+    }
+
+    #[test]
+    fn test_vesting_late_in_the_day() {
+        let reward_token: AccountId = "reward_token".parse().unwrap();
+        let nft: AccountId = "nft".parse().unwrap();
+        let dao: AccountId = "dao".parse().unwrap();
+        let operator: AccountId = "operator".parse().unwrap();
+        let alice = accounts(1);
+
+        let mut contract = Contract::new(dao.clone(), operator.clone(), vec![], reward_token, nft);
+
+        let context = VMContextBuilder::new().predecessor_account_id(dao).build();
+        testing_env!(context);
+        contract.set_vesting_config(Some(VestingConfig {
+            cliff_seconds: 60 * 60,
+            duration_seconds: 24 * 60 * 60,
+        }));
+
+        let morning = DAY + 60 * 60 * SECOND;
+        let late = 2 * DAY - 60 * 60 * SECOND;
+        for (i, timestamp) in [morning, late].into_iter().enumerate() {
+            let context = VMContextBuilder::new()
+                .predecessor_account_id(operator.clone())
+                .block_timestamp(timestamp)
+                .build();
+            testing_env!(context);
+            contract.send_rewards(alice.clone(), U128(100), format!("reward-{i}"), None, None);
+        }
+
+        // the reward sent at 23:00 still has to pass the cliff
+        let schedules = contract.vesting_schedules_of(alice);
+        assert_eq!(schedules.len(), 1);
+        assert_eq!(schedules[0].start, late);
+        assert_eq!(schedules[0].vested(late), 0);
+        assert_eq!(schedules[0].vested(late + 60 * 60 * SECOND - 1), 0);
+        assert_eq!(schedules[0].vested(late + DAY), 200);
+    }
+}
@@ -1,6 +1,22 @@
-use crate::{Contract, ContractExt};
+use crate::{
+    Contract, ContractExt,
+    badge::{Badge, BadgeConfig},
+    donation::{DonationSplit, DonationSplitTotals},
+    ft::TransferPolicy,
+    level::Level,
+    prize::PrizePool,
+    pro_rata::ProRataDistribution,
+    referral::{ReferralConfig, ReferralStatsView},
+    reward::RewardStatus,
+    shop::{Redemption, ShopItem},
+    slash::SlashRecord,
+    stats::{ConfigView, StatsView},
+    streak::{StreakTier, StreakView},
+    stream::RewardStreamView,
+    vesting::{VestingConfig, VestingSchedule},
+};
 use near_contract_standards::non_fungible_token::TokenId;
-use near_sdk::{json_types::U128, near, AccountId};
+use near_sdk::{AccountId, env, json_types::U128, near};
 
 #[near]
 impl Contract {
@@ -13,21 +29,51 @@ impl Contract {
         self.ranking
             .iter()
             .rev()
-            .take(limit as usize)
-            .map(|x| {
+            .filter_map(|x| {
                 let stakers_with_score =
                     x.1.iter()
+                        .filter(|token_id| !self.internal_is_banned(token_id))
                         .map(|token_id| {
                             let staker = self.staker_of(token_id.clone());
                             (token_id.clone(), staker)
                         })
                         .collect::<Vec<_>>();
 
-                (U128(*x.0), stakers_with_score)
+                (!stakers_with_score.is_empty()).then_some((U128(*x.0), stakers_with_score))
             })
+            .take(limit as usize)
             .collect()
     }
 
+    pub fn get_config(&self) -> ConfigView {
+        ConfigView {
+            owner: self.owner.clone(),
+            operator: self.operator.clone(),
+            whitelisted_record_score_ids: self.whitelisted_record_score_ids.clone(),
+            moderators: self.moderators.clone(),
+            reward_token: self.reward_token.clone(),
+            nft: self.nft.clone(),
+        }
+    }
+
+    pub fn get_stats(&self) -> StatsView {
+        let average_score = self
+            .total_score
+            .checked_div(self.scored_token_count.into())
+            .unwrap_or(0);
+
+        StatsView {
+            total_score: U128(self.total_score),
+            total_staked_score: U128(self.total_staked_score),
+            total_distribute: U128(self.total_distribute),
+            total_donation: U128(self.total_donation),
+            total_nft_staked: U128(self.total_nft_staked),
+            scored_tokens: self.scored_token_count,
+            average_score: U128(average_score),
+            top_score: U128(self.ranking.iter().next_back().map_or(0, |x| *x.0)),
+        }
+    }
+
     pub fn primary_nft_of(&self, account_id: AccountId) -> Option<(TokenId, U128)> {
         if let Some(token_id) = self.account_to_token_id.get(&account_id) {
             let score = self.scores.get(token_id).unwrap_or(&0);
@@ -37,6 +83,43 @@ impl Contract {
         }
     }
 
+    /// Staking accounts with their staked NFT, ordered by an id assigned when staking. Pages
+    /// continue from the last returned id + 1, and stay stable while accounts (un)stake.
+    pub fn get_stakers(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u32>,
+    ) -> Vec<(u64, AccountId, TokenId)> {
+        self.stakers
+            .range(from_index.unwrap_or(0)..)
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(id, account_id)| {
+                let token_id = self.account_to_token_id.get(account_id).unwrap();
+                (*id, account_id.clone(), token_id.clone())
+            })
+            .collect()
+    }
+
+    /// Every token that has been scored, staked or not, in the order it was first scored. Tokens
+    /// whose score dropped to zero keep their index, so that pages stay stable.
+    pub fn get_scored_tokens(
+        &self,
+        from_index: Option<u32>,
+        limit: Option<u32>,
+    ) -> Vec<(u32, TokenId, U128)> {
+        let from = from_index.unwrap_or(0);
+        let to = self
+            .scored_tokens
+            .len()
+            .min(from.saturating_add(limit.unwrap_or(50)));
+        (from..to)
+            .map(|index| {
+                let token_id = self.scored_tokens[index].clone();
+                (index, token_id.clone(), self.score_of(token_id))
+            })
+            .collect()
+    }
+
     pub fn staker_of(&self, token_id: TokenId) -> Option<AccountId> {
         self.token_id_to_account.get(&token_id).cloned()
     }
@@ -44,4 +127,216 @@ impl Contract {
     pub fn score_of(&self, token_id: TokenId) -> U128 {
         U128(*self.scores.get(&token_id).unwrap_or(&0))
     }
+
+    pub fn get_referral_config(&self) -> ReferralConfig {
+        self.referral_config.clone()
+    }
+
+    pub fn referral_stats_of(&self, account_id: AccountId) -> ReferralStatsView {
+        let referral = self.referrals.get(&account_id);
+        let stats = self.referrer_stats.get(&account_id);
+
+        ReferralStatsView {
+            referrer: referral.map(|referral| referral.referrer.clone()),
+            referees: stats.map_or(0, |stats| stats.referees),
+            earned: U128(stats.map_or(0, |stats| stats.earned)),
+            generated: U128(referral.map_or(0, |referral| referral.credited)),
+        }
+    }
+
+    pub fn get_donor_leaderboard(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(U128, Vec<AccountId>)> {
+        let from_index = from_index.unwrap_or(0);
+        let limit = limit.unwrap_or(10);
+        self.donor_ranking
+            .iter()
+            .rev()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .map(|(amount, donors)| (U128(*amount), donors.clone()))
+            .collect()
+    }
+
+    pub fn donation_of(&self, account_id: AccountId) -> U128 {
+        U128(*self.donation_amounts.get(&account_id).unwrap_or(&0))
+    }
+
+    pub fn received_donations_of(&self, token_id: TokenId) -> U128 {
+        U128(*self.received_donations.get(&token_id).unwrap_or(&0))
+    }
+
+    pub fn escrowed_donation_of(&self, account_id: AccountId) -> U128 {
+        U128(*self.escrowed_donations.get(&account_id).unwrap_or(&0))
+    }
+
+    pub fn get_total_escrowed(&self) -> U128 {
+        U128(self.total_escrowed)
+    }
+
+    pub fn get_donation_split(&self) -> DonationSplit {
+        self.donation_split.clone()
+    }
+
+    pub fn get_donation_split_totals(&self) -> DonationSplitTotals {
+        self.donation_split_totals.clone()
+    }
+
+    pub fn get_prize_pool(&self, season: u64) -> Option<PrizePool> {
+        self.prize_pools.get(&season).cloned()
+    }
+
+    pub fn get_pro_rata_distribution(&self, id: u64) -> Option<ProRataDistribution> {
+        self.pro_rata_distributions.get(&id).cloned()
+    }
+
+    pub fn get_reward_stream(&self) -> Option<RewardStreamView> {
+        let stream = self.reward_stream.as_ref()?;
+        let (_, streamed) =
+            stream.accumulated(self.internal_total_stream_shares(), env::block_timestamp());
+        let remaining = stream.remaining.0 - streamed;
+
+        Some(RewardStreamView {
+            rate_per_second: stream.rate_per_second,
+            weighting: stream.weighting,
+            remaining: U128(remaining),
+            runway_seconds: (stream.rate_per_second.0 > 0)
+                .then(|| (remaining / stream.rate_per_second.0) as u64),
+        })
+    }
+
+    pub fn pending_staking_rewards(&self, account_id: AccountId) -> U128 {
+        U128(self.internal_pending_staking_rewards(&account_id))
+    }
+
+    pub fn get_vesting_config(&self) -> Option<VestingConfig> {
+        self.vesting_config.clone()
+    }
+
+    pub fn vesting_schedules_of(&self, account_id: AccountId) -> Vec<VestingSchedule> {
+        self.vesting_schedules
+            .get(&account_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn get_total_vesting_liabilities(&self) -> U128 {
+        U128(self.total_vesting)
+    }
+
+    pub fn get_points_per_day(&self, collection: AccountId) -> U128 {
+        self.points_accruals
+            .get(&collection)
+            .map_or(U128(0), |accrual| accrual.points_per_day)
+    }
+
+    pub fn pending_points_of(&self, token_id: TokenId) -> U128 {
+        U128(self.internal_pending_points(&token_id))
+    }
+
+    pub fn get_streak_tiers(&self) -> Vec<StreakTier> {
+        self.streak_tiers.clone()
+    }
+
+    pub fn streak_of(&self, token_id: TokenId) -> StreakView {
+        self.internal_streak_of(&token_id)
+    }
+
+    pub fn get_levels(&self) -> Vec<Level> {
+        self.levels.clone()
+    }
+
+    pub fn level_of(&self, token_id: TokenId) -> u32 {
+        self.internal_level_of(&token_id)
+    }
+
+    pub fn get_badge_config(&self) -> Option<BadgeConfig> {
+        self.badge_config.clone()
+    }
+
+    /// Badges earned by a token, including the ones still being minted.
+    pub fn badges_of(&self, token_id: TokenId) -> Vec<Badge> {
+        self.badges.get(&token_id).cloned().unwrap_or_default()
+    }
+
+    /// Earned badges of a token that still need to be minted with `claim_badges`.
+    pub fn pending_badges_of(&self, token_id: TokenId) -> Vec<Badge> {
+        self.pending_badges
+            .get(&token_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn get_shop_items(
+        &self,
+        from_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Vec<(u64, ShopItem)> {
+        (from_index.unwrap_or(0)..self.shop_item_count)
+            .filter_map(|item_id| {
+                self.shop_items
+                    .get(&item_id)
+                    .map(|item| (item_id, item.clone()))
+            })
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    pub fn get_redemption(&self, redemption_id: u64) -> Option<Redemption> {
+        self.redemptions.get(&redemption_id).cloned()
+    }
+
+    pub fn redemptions_of(&self, account_id: AccountId) -> Vec<(u64, Redemption)> {
+        self.account_redemptions
+            .get(&account_id)
+            .map(|redemption_ids| {
+                redemption_ids
+                    .iter()
+                    .map(|id| (*id, self.redemptions.get(id).cloned().unwrap()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_transfer_policy(&self) -> TransferPolicy {
+        self.transfer_policy.clone()
+    }
+
+    pub fn is_account_banned(&self, account_id: AccountId) -> bool {
+        self.banned_accounts.contains(&account_id)
+    }
+
+    pub fn is_token_banned(&self, token_id: TokenId) -> bool {
+        self.banned_tokens.contains(&token_id)
+    }
+
+    pub fn is_import_sealed(&self) -> bool {
+        self.import_sealed
+    }
+
+    /// Imported staker of a token whose NFT has not been transferred to the rewarder yet.
+    pub fn imported_staker_of(&self, token_id: TokenId) -> Option<AccountId> {
+        self.imported_stakers.get(&token_id).cloned()
+    }
+
+    pub fn get_moderators(&self) -> Vec<AccountId> {
+        self.moderators.clone()
+    }
+
+    /// Audit log of score clawbacks, oldest first.
+    pub fn get_slashes(&self, from_index: Option<u32>, limit: Option<u32>) -> Vec<SlashRecord> {
+        self.slashes
+            .iter()
+            .skip(from_index.unwrap_or(0) as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Status of a reward sent with `send_rewards`, as long as its id is still retained.
+    pub fn reward_status(&self, reward_id: String) -> Option<RewardStatus> {
+        self.reward_statuses.get(&reward_id).copied()
+    }
 }